@@ -0,0 +1,104 @@
+use std::{
+    alloc::{GlobalAlloc, Layout, System},
+    fmt,
+    sync::atomic::{AtomicUsize, Ordering},
+};
+
+// Wraps the system allocator, counting every allocation as it goes. Only
+// compiled in with `--features alloc-stats`, so normal runs pay nothing.
+struct CountingAllocator;
+
+static ALLOCATIONS: AtomicUsize = AtomicUsize::new(0);
+static BYTES_ALLOCATED: AtomicUsize = AtomicUsize::new(0);
+static LIVE_BYTES: AtomicUsize = AtomicUsize::new(0);
+static PEAK_LIVE_BYTES: AtomicUsize = AtomicUsize::new(0);
+
+fn record_alloc(size: usize) {
+    ALLOCATIONS.fetch_add(1, Ordering::Relaxed);
+    BYTES_ALLOCATED.fetch_add(size, Ordering::Relaxed);
+    let live = LIVE_BYTES.fetch_add(size, Ordering::Relaxed) + size;
+    PEAK_LIVE_BYTES.fetch_max(live, Ordering::Relaxed);
+}
+
+fn record_dealloc(size: usize) {
+    LIVE_BYTES.fetch_sub(size, Ordering::Relaxed);
+}
+
+unsafe impl GlobalAlloc for CountingAllocator {
+    unsafe fn alloc(&self, layout: Layout) -> *mut u8 {
+        let ptr = unsafe { System.alloc(layout) };
+        if !ptr.is_null() {
+            record_alloc(layout.size());
+        }
+        ptr
+    }
+
+    unsafe fn alloc_zeroed(&self, layout: Layout) -> *mut u8 {
+        let ptr = unsafe { System.alloc_zeroed(layout) };
+        if !ptr.is_null() {
+            record_alloc(layout.size());
+        }
+        ptr
+    }
+
+    unsafe fn dealloc(&self, ptr: *mut u8, layout: Layout) {
+        unsafe { System.dealloc(ptr, layout) };
+        record_dealloc(layout.size());
+    }
+
+    // A growing Vec counts as a fresh allocation of its new size.
+    unsafe fn realloc(&self, ptr: *mut u8, layout: Layout, new_size: usize) -> *mut u8 {
+        let new_ptr = unsafe { System.realloc(ptr, layout, new_size) };
+        if !new_ptr.is_null() {
+            record_dealloc(layout.size());
+            record_alloc(new_size);
+        }
+        new_ptr
+    }
+}
+
+#[global_allocator]
+static GLOBAL: CountingAllocator = CountingAllocator;
+
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+pub struct Stats {
+    pub allocations: usize,
+    pub bytes_allocated: usize,
+    // Measured above whatever was already live when measuring started.
+    pub peak_live_bytes: usize,
+}
+
+impl fmt::Display for Stats {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "{} allocations, {} bytes allocated, {} bytes peak live",
+            self.allocations, self.bytes_allocated, self.peak_live_bytes
+        )
+    }
+}
+
+pub fn measure<T>(f: impl FnOnce() -> T) -> (T, Stats) {
+    let allocations = ALLOCATIONS.load(Ordering::Relaxed);
+    let bytes_allocated = BYTES_ALLOCATED.load(Ordering::Relaxed);
+    let live = LIVE_BYTES.load(Ordering::Relaxed);
+    PEAK_LIVE_BYTES.store(live, Ordering::Relaxed);
+
+    let result = f();
+
+    let stats = Stats {
+        allocations: ALLOCATIONS.load(Ordering::Relaxed) - allocations,
+        bytes_allocated: BYTES_ALLOCATED.load(Ordering::Relaxed) - bytes_allocated,
+        peak_live_bytes: PEAK_LIVE_BYTES.load(Ordering::Relaxed).saturating_sub(live),
+    };
+    (result, stats)
+}
+
+#[test]
+fn counts_a_vec() {
+    let (_, stats) = measure(|| vec![0_u64; 1000].len());
+    // Other tests allocate concurrently, so these are only lower bounds.
+    assert!(stats.allocations >= 1);
+    assert!(stats.bytes_allocated >= 8000);
+    assert!(stats.peak_live_bytes >= 8000);
+}
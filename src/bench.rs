@@ -0,0 +1,184 @@
+use std::{
+    collections::BTreeMap,
+    env,
+    fs::{self, OpenOptions},
+    io::{self, Write},
+    path::Path,
+    time::SystemTime,
+};
+
+use itertools::Itertools;
+
+use crate::runner;
+
+pub const HISTORY_FILE: &str = "bench-history.txt";
+
+// A day counts as slower once its best time is this much worse than the
+// previous commit's best - anything less is usually just noise.
+const SLOWDOWN: f64 = 1.1;
+
+// The commit HEAD points at, following a branch ref through loose or packed
+// refs. Returns None outside a git checkout or on an unborn branch.
+pub fn current_commit(git_dir: &Path) -> Option<String> {
+    let head = fs::read_to_string(git_dir.join("HEAD")).ok()?;
+    let head = head.trim();
+    let reference = match head.strip_prefix("ref: ") {
+        Some(reference) => reference,
+        None => return Some(head.to_string()),
+    };
+
+    if let Ok(hash) = fs::read_to_string(git_dir.join(reference)) {
+        return Some(hash.trim().to_string());
+    }
+    let packed = fs::read_to_string(git_dir.join("packed-refs")).ok()?;
+    packed
+        .lines()
+        .filter(|l| !l.starts_with('#') && !l.starts_with('^'))
+        .find_map(|l| match l.split_once(' ') {
+            Some((hash, name)) if name == reference => Some(hash.to_string()),
+            _ => None,
+        })
+}
+
+struct Entry {
+    commit: String,
+    day: u8,
+    millis: f64,
+}
+
+// One "<commit> <unix time> <day> <millis>" line per run.
+fn parse_history(contents: &str) -> Vec<Entry> {
+    contents
+        .lines()
+        .filter_map(|l| {
+            let (commit, _, day, millis) = l.split(' ').collect_tuple()?;
+            Some(Entry {
+                commit: commit.to_string(),
+                day: day.parse().ok()?,
+                millis: millis.parse().ok()?,
+            })
+        })
+        .collect()
+}
+
+pub fn bench(days: &[u8], runs: usize, history: &Path) -> io::Result<()> {
+    let exe = env::current_exe()?;
+    let commit = current_commit(Path::new(".git")).unwrap_or_else(|| "unknown".to_string());
+    let now = SystemTime::now()
+        .duration_since(SystemTime::UNIX_EPOCH)
+        .unwrap()
+        .as_secs();
+
+    let mut file = OpenOptions::new().create(true).append(true).open(history)?;
+    for &day in days {
+        let mut timings = vec![];
+        let mut memory = BTreeMap::new();
+        for _ in 0..runs.max(1) {
+            let run = runner::run(&exe, day);
+            if !run.success {
+                println!("Day {} failed - not recorded.", day);
+                timings.clear();
+                memory.clear();
+                break;
+            }
+            timings.push(run.elapsed.as_secs_f64() * 1000.0);
+            memory = runner::parse_memory(&run.stdout);
+        }
+        for millis in &timings {
+            writeln!(file, "{} {} {} {:.3}", commit, now, day, millis)?;
+        }
+        if let Some(best) = timings.iter().cloned().reduce(f64::min) {
+            println!(
+                "Day {}: best {:.2} ms over {} runs",
+                day,
+                best,
+                timings.len()
+            );
+        }
+        // Only printed by a binary built with `--features alloc-stats`.
+        for (part, stats) in &memory {
+            println!("  part {}: {}", part, stats);
+        }
+    }
+    Ok(())
+}
+
+// Per commit, in the order they were first benchmarked: runs, best, mean and
+// whether the best time regressed against the previous commit.
+fn trend(entries: &[Entry], day: u8) -> Vec<(String, usize, f64, f64, bool)> {
+    let mut commits: Vec<(String, Vec<f64>)> = vec![];
+    for e in entries.iter().filter(|e| e.day == day) {
+        match commits.iter_mut().find(|(c, _)| *c == e.commit) {
+            Some((_, timings)) => timings.push(e.millis),
+            None => commits.push((e.commit.clone(), vec![e.millis])),
+        }
+    }
+
+    let mut rows = vec![];
+    let mut previous_best = None;
+    for (commit, timings) in commits {
+        let best = timings.iter().cloned().fold(f64::INFINITY, f64::min);
+        let mean = timings.iter().sum::<f64>() / timings.len() as f64;
+        let slower = matches!(previous_best, Some(p) if best > p * SLOWDOWN);
+        rows.push((commit, timings.len(), best, mean, slower));
+        previous_best = Some(best);
+    }
+    rows
+}
+
+pub fn show_history(day: u8, history: &Path) -> io::Result<()> {
+    // Nothing has been benchmarked yet on a fresh checkout.
+    let contents = match fs::read_to_string(history) {
+        Err(e) if e.kind() == io::ErrorKind::NotFound => String::new(),
+        contents => contents?,
+    };
+    let entries = parse_history(&contents);
+    let rows = trend(&entries, day);
+    if rows.is_empty() {
+        println!("No benchmarks recorded for day {}", day);
+        return Ok(());
+    }
+
+    println!(
+        "{:<12} {:>5} {:>12} {:>12}",
+        "commit", "runs", "best ms", "mean ms"
+    );
+    for (commit, runs, best, mean, slower) in rows {
+        println!(
+            "{:<12} {:>5} {:>12.2} {:>12.2}{}",
+            &commit[..commit.len().min(12)],
+            runs,
+            best,
+            mean,
+            if slower { "  <- slower" } else { "" }
+        );
+    }
+    Ok(())
+}
+
+#[test]
+fn commit_from_packed_refs() {
+    let git_dir = env::temp_dir().join("aoc22-bench-git");
+    fs::create_dir_all(&git_dir).unwrap();
+    fs::write(git_dir.join("HEAD"), "ref: refs/heads/main\n").unwrap();
+    fs::write(
+        git_dir.join("packed-refs"),
+        "# pack-refs with: peeled fully-peeled sorted\nabc123 refs/heads/main\ndef456 refs/tags/v1\n",
+    )
+    .unwrap();
+    assert_eq!(Some("abc123".to_string()), current_commit(&git_dir));
+}
+
+#[test]
+fn marks_slower_commit() {
+    let history = "aaa 1 16 100.0\naaa 1 16 90.0\nbbb 2 16 95.0\nccc 3 16 120.0\nccc 3 1 1.0\n";
+    let rows = trend(&parse_history(history), 16);
+    let slower = rows
+        .iter()
+        .map(|r| (r.0.as_str(), r.1, r.4))
+        .collect::<Vec<_>>();
+    assert_eq!(
+        vec![("aaa", 2, false), ("bbb", 1, false), ("ccc", 1, true)],
+        slower
+    );
+}
@@ -0,0 +1,208 @@
+use std::{
+    cell::{OnceCell, RefCell},
+    collections::BTreeMap,
+    fmt,
+    fs::{self, File},
+    io::{self, BufRead, BufReader, Write},
+    path::PathBuf,
+    str::FromStr,
+};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Part {
+    A,
+    B,
+}
+
+impl fmt::Display for Part {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Part::A => write!(f, "A"),
+            Part::B => write!(f, "B"),
+        }
+    }
+}
+
+// Forward to print!/eprint! rather than io::stdout()/io::stderr() so that
+// output from tests is still captured by the test harness.
+struct Stdout;
+struct Stderr;
+
+impl Write for Stdout {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        print!("{}", String::from_utf8_lossy(buf));
+        Ok(buf.len())
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        io::stdout().flush()
+    }
+}
+
+impl Write for Stderr {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        eprint!("{}", String::from_utf8_lossy(buf));
+        Ok(buf.len())
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        io::stderr().flush()
+    }
+}
+
+// A writer that keeps everything written to it, for looking at afterwards.
+#[cfg(test)]
+#[derive(Clone, Default)]
+pub struct Captured(std::rc::Rc<RefCell<Vec<u8>>>);
+
+#[cfg(test)]
+impl Captured {
+    pub fn contents(&self) -> String {
+        String::from_utf8_lossy(&self.0.borrow()).into_owned()
+    }
+}
+
+#[cfg(test)]
+impl Write for Captured {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        self.0.borrow_mut().extend_from_slice(buf);
+        Ok(buf.len())
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        Ok(())
+    }
+}
+
+// Everything a day needs besides its code: which input to solve, which parts
+// to solve, any tweakable constants (e.g. day 15's target row), and where to
+// write answers and diagnostics.
+pub struct Context {
+    // Replaces the day's bundled input when set, e.g. a snippet pasted into the REPL.
+    pub input: Option<String>,
+    // Like `input`, but read from disk - only once a day asks for it, and
+    // streamed rather than loaded by days that can.
+    pub input_file: Option<PathBuf>,
+    loaded: OnceCell<String>,
+    // Only this part is solved when set.
+    pub part: Option<Part>,
+    config: BTreeMap<String, String>,
+    answers: RefCell<Box<dyn Write>>,
+    diagnostics: RefCell<Box<dyn Write>>,
+}
+
+// Answers go to stdout and diagnostics to stderr.
+impl Default for Context {
+    fn default() -> Context {
+        Context::with_writers(Stdout, Stderr)
+    }
+}
+
+impl Context {
+    pub fn with_writers(
+        answers: impl Write + 'static,
+        diagnostics: impl Write + 'static,
+    ) -> Context {
+        Context {
+            input: None,
+            input_file: None,
+            loaded: OnceCell::new(),
+            part: None,
+            config: BTreeMap::new(),
+            answers: RefCell::new(Box::new(answers)),
+            diagnostics: RefCell::new(Box::new(diagnostics)),
+        }
+    }
+
+    // Writes both channels into buffers instead, returned as (answers, diagnostics).
+    #[cfg(test)]
+    pub fn captured() -> (Context, Captured, Captured) {
+        let (answers, diagnostics) = (Captured::default(), Captured::default());
+        let ctx = Context::with_writers(answers.clone(), diagnostics.clone());
+        (ctx, answers, diagnostics)
+    }
+
+    pub fn input<'a>(&'a self, bundled: &'a str) -> &'a str {
+        if let Some(path) = &self.input_file {
+            return self.loaded.get_or_init(|| {
+                fs::read_to_string(path)
+                    .unwrap_or_else(|e| panic!("Can't read {}: {}", path.display(), e))
+            });
+        }
+        self.input.as_deref().unwrap_or(bundled)
+    }
+
+    pub fn reader<'a>(&'a self, bundled: &'a str) -> Box<dyn BufRead + 'a> {
+        match &self.input_file {
+            Some(path) => {
+                Box::new(BufReader::new(File::open(path).unwrap_or_else(|e| {
+                    panic!("Can't read {}: {}", path.display(), e)
+                })))
+            }
+            None => Box::new(self.input(bundled).as_bytes()),
+        }
+    }
+
+    pub fn wants(&self, part: Part) -> bool {
+        self.part.is_none_or(|p| p == part)
+    }
+
+    pub fn set(&mut self, key: &str, value: &str) {
+        self.config.insert(key.to_string(), value.to_string());
+    }
+
+    pub fn settings(&self) -> impl Iterator<Item = (&String, &String)> {
+        self.config.iter()
+    }
+
+    pub fn config<T: FromStr>(&self, key: &str, default: T) -> T {
+        match self.config.get(key) {
+            Some(value) => value
+                .parse()
+                .unwrap_or_else(|_| panic!("Bad value for {}: {}", key, value)),
+            None => default,
+        }
+    }
+
+    // Answers spanning several lines, like day 10's screen, start on their own line.
+    pub fn answer(&self, part: Part, answer: impl fmt::Display) {
+        if !self.wants(part) {
+            return;
+        }
+        let answer = answer.to_string();
+        let separator = if answer.contains('\n') { "\n" } else { " " };
+        let mut out = self.answers.borrow_mut();
+        writeln!(out, "Part {} is:{}{}", part, separator, answer).unwrap();
+    }
+
+    // Progress and debugging output that isn't part of any answer.
+    pub fn diagnostic(&self, message: impl fmt::Display) {
+        writeln!(self.diagnostics.borrow_mut(), "{}", message).unwrap();
+    }
+
+    pub fn flush(&self) {
+        self.answers.borrow_mut().flush().unwrap();
+        self.diagnostics.borrow_mut().flush().unwrap();
+    }
+}
+
+#[test]
+fn config_falls_back_to_default() {
+    let mut ctx = Context::default();
+    assert_eq!(2000000, ctx.config("target_y", 2000000));
+    ctx.set("target_y", "10");
+    assert_eq!(10, ctx.config("target_y", 2000000));
+}
+
+#[test]
+fn answers_and_diagnostics_are_separate() {
+    let (ctx, answers, diagnostics) = Context::captured();
+    ctx.diagnostic("Parsing input");
+    ctx.answer(Part::A, 24000);
+    ctx.answer(Part::B, "#..#\n#..#");
+    assert_eq!(
+        "Part A is: 24000\nPart B is:\n#..#\n#..#\n",
+        answers.contents()
+    );
+    assert_eq!("Parsing input\n", diagnostics.contents());
+}
@@ -1,33 +1,271 @@
-pub fn day01() {
-    let readings = include_str!("../inputs/day01.txt");
-    let groups = readings.split("\n\n").map(|elf_group| {
-        elf_group
-            .lines()
-            .map(|l| l.parse::<u32>().unwrap())
-            .sum::<u32>()
-    });
-
-    let top_3 = groups.fold([0, 0, 0], |mut acc, elem| {
-        // lol. Kinda cute though.
-        if elem > acc[0] {
-            if acc[0] > acc[1] {
-                if acc[1] > acc[2] {
-                    acc[2] = acc[1];
-                }
-                acc[1] = acc[0]
+use std::{
+    cmp::Reverse,
+    collections::BinaryHeap,
+    fmt,
+    io::{self, BufRead},
+};
+
+use crate::context::{Context, Part};
+
+#[derive(Debug)]
+enum TallyError {
+    Io(io::Error),
+    NotANumber { line: usize, text: String },
+    Overflow { line: usize },
+}
+
+impl fmt::Display for TallyError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            TallyError::Io(e) => write!(f, "{}", e),
+            TallyError::NotANumber { line, text } => {
+                write!(f, "line {}: {:?} is not a number", line, text)
+            }
+            TallyError::Overflow { line } => write!(f, "line {}: group total overflows", line),
+        }
+    }
+}
+
+// Totals of each blank-line-separated group, read a line at a time so only
+// the current line is ever held in memory. Runs of blank lines, CRLF endings
+// and trailing whitespace are all fine.
+struct Groups<R> {
+    reader: R,
+    line: usize,
+    buf: String,
+    failed: bool,
+}
+
+impl<R: BufRead> Groups<R> {
+    fn new(reader: R) -> Groups<R> {
+        Groups {
+            reader,
+            line: 0,
+            buf: String::new(),
+            failed: false,
+        }
+    }
+
+    fn next_group(&mut self) -> Result<Option<u64>, TallyError> {
+        let mut total = None;
+        loop {
+            self.buf.clear();
+            if self
+                .reader
+                .read_line(&mut self.buf)
+                .map_err(TallyError::Io)?
+                == 0
+            {
+                return Ok(total);
             }
-            acc[0] = elem;
-        } else if elem > acc[1] {
-            if acc[1] > acc[2] {
-                acc[2] = acc[1];
+            self.line += 1;
+
+            let text = self.buf.trim();
+            if text.is_empty() {
+                if total.is_some() {
+                    return Ok(total);
+                }
+                continue;
             }
-            acc[1] = elem;
-        } else if elem > acc[2] {
-            acc[2] = elem;
+            let calories = text.parse::<u64>().map_err(|_| TallyError::NotANumber {
+                line: self.line,
+                text: text.to_string(),
+            })?;
+            let sum = total.unwrap_or(0u64).checked_add(calories);
+            total = Some(sum.ok_or(TallyError::Overflow { line: self.line })?);
+        }
+    }
+}
+
+impl<R: BufRead> Iterator for Groups<R> {
+    type Item = Result<u64, TallyError>;
+
+    // Stops after the first error - there's no sensible way to carry on.
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.failed {
+            return None;
+        }
+        let group = self.next_group();
+        self.failed = group.is_err();
+        group.transpose()
+    }
+}
+
+// The `n` largest totals seen so far with their indices. Ties go to whichever
+// group came first.
+struct TopN {
+    n: usize,
+    // A min-heap, so the weakest of the best is always on top.
+    heap: BinaryHeap<Reverse<(u64, Reverse<usize>)>>,
+}
+
+impl TopN {
+    fn new(n: usize) -> TopN {
+        TopN {
+            n,
+            heap: BinaryHeap::with_capacity(n + 1),
+        }
+    }
+
+    fn push(&mut self, i: usize, total: u64) {
+        self.heap.push(Reverse((total, Reverse(i))));
+        if self.heap.len() > self.n {
+            self.heap.pop();
         }
-        acc
-    });
+    }
+
+    // Largest first.
+    fn into_sorted_vec(self) -> Vec<(usize, u64)> {
+        self.heap
+            .into_sorted_vec()
+            .into_iter()
+            .map(|Reverse((total, Reverse(i)))| (i, total))
+            .collect()
+    }
+}
+
+// Past this many groups the median is dropped rather than keep every total.
+const MEDIAN_LIMIT: usize = 1 << 20;
+
+#[derive(Debug, PartialEq)]
+struct Summary {
+    count: usize,
+    min: u64,
+    max: u64,
+    mean: f64,
+    median: Option<f64>,
+}
+
+impl fmt::Display for Summary {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "{} groups: min {}, max {}, mean {:.1}",
+            self.count, self.min, self.max, self.mean
+        )?;
+        match self.median {
+            Some(median) => write!(f, ", median {:.1}", median),
+            None => write!(f, ", too many groups for a median"),
+        }
+    }
+}
+
+#[derive(Default)]
+struct Stats {
+    count: usize,
+    min: u64,
+    max: u64,
+    sum: u128,
+    totals: Vec<u64>,
+}
+
+impl Stats {
+    fn push(&mut self, total: u64) {
+        self.min = if self.count == 0 {
+            total
+        } else {
+            self.min.min(total)
+        };
+        self.max = self.max.max(total);
+        self.sum += total as u128;
+        self.count += 1;
+        if self.count <= MEDIAN_LIMIT {
+            self.totals.push(total);
+        } else {
+            self.totals = vec![];
+        }
+    }
+
+    fn summary(mut self) -> Option<Summary> {
+        if self.count == 0 {
+            return None;
+        }
+        let median = if self.count <= MEDIAN_LIMIT {
+            self.totals.sort_unstable();
+            let (low, high) = ((self.count - 1) / 2, self.count / 2);
+            Some((self.totals[low] as f64 + self.totals[high] as f64) / 2.0)
+        } else {
+            None
+        };
+        Some(Summary {
+            count: self.count,
+            min: self.min,
+            max: self.max,
+            mean: self.sum as f64 / self.count as f64,
+            median,
+        })
+    }
+}
+
+// The top `n` groups (index, total) and a summary of all of them.
+type Tally = (Vec<(usize, u64)>, Option<Summary>);
+
+// Everything is worked out in a single pass over the input.
+fn tally(reader: impl BufRead, n: usize) -> Result<Tally, TallyError> {
+    let mut top = TopN::new(n);
+    let mut stats = Stats::default();
+    for (i, total) in Groups::new(reader).enumerate() {
+        let total = total?;
+        top.push(i, total);
+        stats.push(total);
+    }
+    Ok((top.into_sorted_vec(), stats.summary()))
+}
+
+pub fn day01(ctx: &Context) {
+    // Part A is the largest group, so there has to be at least one.
+    let n = ctx.config("top", 3);
+    if n == 0 {
+        panic!("Bad value for top: 0 - it has to be at least 1");
+    }
+    let readings = ctx.reader(include_str!("../inputs/day01.txt"));
+    let (top, summary) = match tally(readings, n) {
+        Ok(tally) => tally,
+        Err(e) => panic!("Bad calorie list: {}", e),
+    };
+
+    for (i, total) in &top {
+        ctx.diagnostic(format!("Elf {}: {}", i + 1, total));
+    }
+    if let Some(summary) = summary {
+        ctx.diagnostic(summary);
+    }
+
+    if let Some((_, most)) = top.first() {
+        ctx.answer(Part::A, most);
+        ctx.answer(Part::B, top.iter().map(|(_, t)| t).sum::<u64>());
+    }
+}
+
+#[test]
+fn sample_input() {
+    let input = "1000\n2000\n3000\n\n4000\n\n5000\n6000\n\n7000\n8000\n9000\n\n10000";
+    let (top, summary) = tally(input.as_bytes(), 3).unwrap();
+    assert_eq!(vec![(3, 24000), (2, 11000), (4, 10000)], top);
+    assert_eq!(
+        Some(Summary {
+            count: 5,
+            min: 4000,
+            max: 24000,
+            mean: 11000.0,
+            median: Some(10000.0),
+        }),
+        summary
+    );
+
+    // Equal totals keep the earlier elf.
+    let (top, _) = tally("6000\n\n4000\n\n6000\n\n4000".as_bytes(), 2).unwrap();
+    assert_eq!(vec![(0, 6000), (2, 6000)], top);
+}
+
+#[test]
+fn messy_input() {
+    let input = "\r\n4000000000 \r\n4000000000\r\n\r\n\r\n\r\n1\r\n";
+    let (top, _) = tally(input.as_bytes(), 3).unwrap();
+    assert_eq!(vec![(0, 8000000000), (1, 1)], top);
 
-    println!("Part A answer is: {:?}", top_3.iter().max().unwrap());
-    println!("Part B answer is: {:?}", top_3.iter().sum::<u32>());
+    let err = tally("100\n\n200\n2OO\n".as_bytes(), 3).unwrap_err();
+    assert_eq!("line 4: \"2OO\" is not a number", err.to_string());
+    let err = tally("18446744073709551615\n1\n".as_bytes(), 3).unwrap_err();
+    assert_eq!("line 2: group total overflows", err.to_string());
 }
@@ -1,98 +1,460 @@
-use std::str::FromStr;
+use std::{cmp::Reverse, collections::BTreeMap, fs, str::FromStr};
 
-use regex::Regex;
+use itertools::Itertools;
 
-#[derive(Debug)]
-struct ParseError;
+use crate::{
+    context::{Context, Part},
+    validate::Diagnostic,
+};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Outcome {
+    Lose,
+    Draw,
+    Win,
+}
+
+const OUTCOMES: [Outcome; 3] = [Outcome::Lose, Outcome::Draw, Outcome::Win];
+
+impl Outcome {
+    fn name(self) -> &'static str {
+        match self {
+            Outcome::Lose => "lose",
+            Outcome::Draw => "draw",
+            Outcome::Win => "win",
+        }
+    }
+}
 
 #[derive(Debug)]
-enum Choice {
-    Rock = 1,
-    Paper = 2,
-    Scissors = 3,
+struct Choice {
+    name: String,
+    score: u32,
+    // What stands for this choice in the first and second columns of the guide.
+    theirs: String,
+    mine: String,
 }
 
+// A game's rules, loaded from a file like inputs/day02-rules.txt. Any number
+// of choices works as long as every pair has a winner and every choice both
+// beats and loses to something.
 #[derive(Debug)]
-enum GameResult {
-    Win = 6,
-    Draw = 3,
-    Lose = 0,
+struct Rules {
+    choices: Vec<Choice>,
+    // beats[a][b] is whether choice a beats choice b.
+    beats: Vec<Vec<bool>>,
+    // Score and symbol of each outcome, in the order of OUTCOMES.
+    outcomes: Vec<(u32, String)>,
 }
 
-fn contest(opponent: Choice, me: Choice) -> GameResult {
-    if opponent as usize == me as usize {
-        GameResult::Draw
-    } else if opponent as usize == me as usize % 3 + 1 {
-        GameResult::Lose
-    } else {
-        GameResult::Win
-    }
+fn parse_score(index: usize, score: &str) -> Result<u32, Diagnostic> {
+    score
+        .parse()
+        .map_err(|_| Diagnostic::at(index, format!("{:?} is not a score", score)))
 }
 
-fn i_should_pick(opponent: Choice, result: GameResult) -> Choice {
-    // Bleugh
-    match result {
-        GameResult::Win => Choice::from_u32(opponent as u32 % 3 + 1),
-        GameResult::Draw => opponent,
-        GameResult::Lose => Choice::from_u32((opponent as u32 + 1) % 3 + 1),
+impl FromStr for Rules {
+    type Err = Diagnostic;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let mut choices: Vec<Choice> = vec![];
+        let mut wins = vec![];
+        let mut outcomes = vec![None; OUTCOMES.len()];
+        for (i, line) in s.lines().enumerate() {
+            let words = line
+                .split('#')
+                .next()
+                .unwrap()
+                .split_whitespace()
+                .collect::<Vec<_>>();
+            match words[..] {
+                [] => {}
+                ["choice", name, score, theirs, mine] => {
+                    if choices.iter().any(|c| c.name == name) {
+                        return Err(Diagnostic::at(i, format!("{} is already a choice", name)));
+                    }
+                    if choices.iter().any(|c| c.theirs == theirs || c.mine == mine) {
+                        return Err(Diagnostic::at(
+                            i,
+                            format!("{}'s symbols are already used", name),
+                        ));
+                    }
+                    choices.push(Choice {
+                        name: name.to_string(),
+                        score: parse_score(i, score)?,
+                        theirs: theirs.to_string(),
+                        mine: mine.to_string(),
+                    });
+                }
+                [winner, "beats", loser] => wins.push((i, winner, loser)),
+                ["outcome", name, score, symbol] => {
+                    let outcome =
+                        OUTCOMES
+                            .iter()
+                            .position(|o| o.name() == name)
+                            .ok_or_else(|| {
+                                Diagnostic::at(i, format!("{:?} is not lose, draw or win", name))
+                            })?;
+                    if outcomes[outcome].is_some() {
+                        return Err(Diagnostic::at(i, format!("{} already has a score", name)));
+                    }
+                    if outcomes.iter().flatten().any(|(_, s)| s == symbol) {
+                        return Err(Diagnostic::at(
+                            i,
+                            format!("{}'s symbol is already used", name),
+                        ));
+                    }
+                    outcomes[outcome] = Some((parse_score(i, score)?, symbol.to_string()));
+                }
+                _ => {
+                    return Err(Diagnostic::at(
+                        i,
+                        format!("can't understand {:?}", line.trim()),
+                    ))
+                }
+            }
+        }
+
+        let find = |i, name| {
+            choices
+                .iter()
+                .position(|c| c.name == name)
+                .ok_or_else(|| Diagnostic::at(i, format!("{} is not a choice", name)))
+        };
+        let mut beats = vec![vec![false; choices.len()]; choices.len()];
+        for (i, winner, loser) in wins {
+            let (winner, loser) = (find(i, winner)?, find(i, loser)?);
+            if winner == loser || beats[loser][winner] {
+                return Err(Diagnostic::at(i, "contradicts an earlier rule"));
+            }
+            beats[winner][loser] = true;
+        }
+
+        for (a, choice) in choices.iter().enumerate() {
+            if let Some(b) = (a + 1..choices.len()).find(|&b| !beats[a][b] && !beats[b][a]) {
+                return Err(Diagnostic::new(format!(
+                    "neither {} nor {} beats the other",
+                    choice.name, choices[b].name
+                )));
+            }
+            if !beats[a].contains(&true) || !beats.iter().any(|row| row[a]) {
+                return Err(Diagnostic::new(format!(
+                    "{} has to both beat and lose to something",
+                    choice.name
+                )));
+            }
+        }
+        let outcomes = outcomes
+            .into_iter()
+            .zip(OUTCOMES)
+            .map(|(o, outcome)| {
+                o.ok_or_else(|| Diagnostic::new(format!("no score for a {}", outcome.name())))
+            })
+            .collect::<Result<_, _>>()?;
+
+        Ok(Rules {
+            choices,
+            beats,
+            outcomes,
+        })
     }
 }
 
-impl Choice {
-    fn from_u32(value: u32) -> Choice {
-        match value {
-            1 => Choice::Rock,
-            2 => Choice::Paper,
-            3 => Choice::Scissors,
-            _ => panic!("Unknown value: {}", value),
+#[derive(Debug, Clone, Copy)]
+enum Reading {
+    // The second column is what I play...
+    Choice,
+    // ...or how the round should end.
+    Outcome,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum Meaning {
+    Choice(usize),
+    Outcome(Outcome),
+}
+
+// What each symbol in the guide's second column stands for.
+type Mapping = BTreeMap<String, Meaning>;
+
+#[derive(Debug)]
+struct Round<'a> {
+    // Line of the guide, counting from zero.
+    index: usize,
+    theirs: usize,
+    second: &'a str,
+}
+
+#[derive(Debug, Default, PartialEq)]
+struct Tally {
+    score: u32,
+    wins: usize,
+    draws: usize,
+    losses: usize,
+}
+
+impl Rules {
+    fn outcome(&self, theirs: usize, mine: usize) -> Outcome {
+        if self.beats[mine][theirs] {
+            Outcome::Win
+        } else if self.beats[theirs][mine] {
+            Outcome::Lose
+        } else {
+            Outcome::Draw
         }
     }
 
-    fn from_str(s: &str) -> Result<Self, ParseError> {
-        match s {
-            "A" | "X" => Ok(Choice::Rock),
-            "B" | "Y" => Ok(Choice::Paper),
-            "C" | "Z" => Ok(Choice::Scissors),
-            _ => Err(ParseError),
+    // With more than three choices there can be several ways to win or lose -
+    // take whichever scores most, or the first listed if they tie.
+    fn pick(&self, theirs: usize, outcome: Outcome) -> usize {
+        (0..self.choices.len())
+            .rev()
+            .filter(|&mine| self.outcome(theirs, mine) == outcome)
+            .max_by_key(|&mine| self.choices[mine].score)
+            .unwrap()
+    }
+
+    // The meaning the rules file gives the second column.
+    fn mapping(&self, reading: Reading) -> Mapping {
+        match reading {
+            Reading::Choice => self
+                .choices
+                .iter()
+                .enumerate()
+                .map(|(i, c)| (c.mine.clone(), Meaning::Choice(i)))
+                .collect(),
+            Reading::Outcome => self
+                .outcomes
+                .iter()
+                .zip(OUTCOMES)
+                .map(|((_, symbol), o)| (symbol.clone(), Meaning::Outcome(o)))
+                .collect(),
+        }
+    }
+
+    fn describe(&self, mapping: &Mapping) -> String {
+        mapping
+            .iter()
+            .map(|(symbol, meaning)| match meaning {
+                Meaning::Choice(c) => format!("{}={}", symbol, self.choices[*c].name),
+                Meaning::Outcome(o) => format!("{}={}", symbol, o.name()),
+            })
+            .join(" ")
+    }
+
+    fn parse_round<'a>(&self, index: usize, round: &'a str) -> Result<Round<'a>, Diagnostic> {
+        let (theirs, second) = round
+            .split_whitespace()
+            .collect_tuple()
+            .ok_or_else(|| Diagnostic::at(index, format!("{:?} is not two symbols", round)))?;
+        let theirs = self
+            .choices
+            .iter()
+            .position(|c| c.theirs == theirs)
+            .ok_or_else(|| {
+                Diagnostic::at(index, format!("{:?} is not an opponent's choice", theirs))
+            })?;
+        Ok(Round {
+            index,
+            theirs,
+            second,
+        })
+    }
+}
+
+fn parse_guide<'a>(rules: &Rules, guide: &'a str) -> Result<Vec<Round<'a>>, Diagnostic> {
+    guide
+        .lines()
+        .enumerate()
+        .filter(|(_, round)| !round.trim().is_empty())
+        .map(|(i, round)| rules.parse_round(i, round))
+        .collect()
+}
+
+fn tally(rules: &Rules, rounds: &[Round], mapping: &Mapping) -> Result<Tally, Diagnostic> {
+    let mut tally = Tally::default();
+    for round in rounds {
+        let mine = match mapping.get(round.second) {
+            Some(Meaning::Choice(mine)) => *mine,
+            Some(Meaning::Outcome(outcome)) => rules.pick(round.theirs, *outcome),
+            None => {
+                let message = format!("{:?} is not a known symbol", round.second);
+                return Err(Diagnostic::at(round.index, message));
+            }
+        };
+        let outcome = rules.outcome(round.theirs, mine);
+        tally.score += rules.choices[mine].score + rules.outcomes[outcome as usize].0;
+        match outcome {
+            Outcome::Win => tally.wins += 1,
+            Outcome::Draw => tally.draws += 1,
+            Outcome::Lose => tally.losses += 1,
         }
     }
+    Ok(tally)
 }
 
-impl FromStr for GameResult {
-    type Err = ParseError;
+// Every way of giving each symbol in the second column a different choice, and
+// every way of giving them different outcomes.
+fn mappings(rules: &Rules, rounds: &[Round]) -> Vec<Mapping> {
+    let symbols = rounds
+        .iter()
+        .map(|r| r.second)
+        .unique()
+        .sorted()
+        .collect::<Vec<_>>();
+    let choices = (0..rules.choices.len())
+        .map(Meaning::Choice)
+        .collect::<Vec<_>>();
+    let outcomes = OUTCOMES
+        .iter()
+        .map(|&o| Meaning::Outcome(o))
+        .collect::<Vec<_>>();
+    [choices, outcomes]
+        .iter()
+        .flat_map(|meanings| meanings.iter().cloned().permutations(symbols.len()))
+        .map(|meanings| {
+            symbols
+                .iter()
+                .map(|s| s.to_string())
+                .zip(meanings)
+                .collect()
+        })
+        .collect()
+}
 
-    fn from_str(s: &str) -> Result<Self, Self::Err> {
-        match s {
-            "X" => Ok(GameResult::Lose),
-            "Y" => Ok(GameResult::Draw),
-            "Z" => Ok(GameResult::Win),
-            _ => Err(ParseError),
+// Scores the guide under every mapping, best first.
+fn analyse(ctx: &Context, rules: &Rules, rounds: &[Round]) {
+    let mut results = mappings(rules, rounds)
+        .into_iter()
+        .map(|mapping| (tally(rules, rounds, &mapping).unwrap(), mapping))
+        .collect::<Vec<_>>();
+    results.sort_by_key(|(tally, _)| Reverse(tally.score));
+
+    ctx.diagnostic(format!(
+        "{} rounds, {} mappings",
+        rounds.len(),
+        results.len()
+    ));
+    for (tally, mapping) in &results {
+        ctx.diagnostic(format!(
+            "{:<50} {:>8} ({} won, {} drawn, {} lost)",
+            rules.describe(mapping),
+            tally.score,
+            tally.wins,
+            tally.draws,
+            tally.losses
+        ));
+    }
+    if let (Some((best, b)), Some((worst, w))) = (results.first(), results.last()) {
+        ctx.diagnostic(format!("Best: {} scores {}", rules.describe(b), best.score));
+        ctx.diagnostic(format!(
+            "Worst: {} scores {}",
+            rules.describe(w),
+            worst.score
+        ));
+    }
+}
+
+fn load_rules(ctx: &Context) -> Rules {
+    let path = ctx.config("rules", String::new());
+    let text = if path.is_empty() {
+        include_str!("../inputs/day02-rules.txt").to_string()
+    } else {
+        fs::read_to_string(&path).unwrap_or_else(|e| panic!("Can't read {}: {}", path, e))
+    };
+    text.parse().unwrap_or_else(|e| panic!("Bad rules: {}", e))
+}
+
+pub fn day02(ctx: &Context) {
+    let rules = load_rules(ctx);
+    let guide = ctx.input(include_str!("../inputs/day02.txt"));
+    let rounds = parse_guide(&rules, guide).unwrap_or_else(|e| panic!("Bad strategy guide: {}", e));
+
+    if ctx.config("analyse", false) {
+        analyse(ctx, &rules, &rounds);
+    }
+    for (part, reading) in [(Part::A, Reading::Choice), (Part::B, Reading::Outcome)] {
+        if ctx.wants(part) {
+            match tally(&rules, &rounds, &rules.mapping(reading)) {
+                Ok(tally) => ctx.answer(part, tally.score),
+                Err(e) => panic!("Bad strategy guide: {}", e),
+            }
         }
     }
 }
 
-fn score_game_strategy_1(game: &str) -> u32 {
-    let re = Regex::new(r"([ABC]) ([XYZ])").unwrap();
-    let cap = re.captures_iter(game).next().unwrap();
-    let opponent = Choice::from_str(&cap[1]).unwrap();
-    let me = Choice::from_str(&cap[2]).unwrap();
-    me as u32 + contest(opponent, me) as u32
+#[cfg(test)]
+fn score_guide(rules: &Rules, guide: &str, reading: Reading) -> Result<u32, Diagnostic> {
+    let rounds = parse_guide(rules, guide)?;
+    Ok(tally(rules, &rounds, &rules.mapping(reading))?.score)
 }
 
-fn score_game_strategy_2(game: &str) -> u32 {
-    let re = Regex::new(r"([ABC]) ([XYZ])").unwrap();
-    let cap = re.captures_iter(game).next().unwrap();
-    let opponent = Choice::from_str(&cap[1]).unwrap();
-    let result = GameResult::from_str(&cap[2]).unwrap();
-    result as u32 + i_should_pick(opponent, result) as u32
+#[test]
+fn sample_input() {
+    let rules = include_str!("../inputs/day02-rules.txt")
+        .parse::<Rules>()
+        .unwrap();
+    let guide = "A Y\nB X\nC Z";
+    assert_eq!(15, score_guide(&rules, guide, Reading::Choice).unwrap());
+    assert_eq!(12, score_guide(&rules, guide, Reading::Outcome).unwrap());
+
+    let err = score_guide(&rules, "A Y\nB Q", Reading::Choice).unwrap_err();
+    assert_eq!("line 2: \"Q\" is not a known symbol", err.to_string());
 }
 
-pub fn day02() {
-    let games = include_str!("../inputs/day02.txt").lines();
+#[test]
+fn lizard_spock() {
+    let rules = include_str!("../inputs/day02-rpsls.txt")
+        .parse::<Rules>()
+        .unwrap();
+    // Spock vaporizes rock, scissors decapitates lizard, lizard draws.
+    let guide = "A Z\nD X\nD Y";
+    assert_eq!(
+        5 + 6 + 3 + 6 + 4 + 3,
+        score_guide(&rules, guide, Reading::Choice).unwrap()
+    );
+    // Against rock, Spock beats paper to the win; losing to lizard is best
+    // done with Spock too, and a draw is lizard.
+    assert_eq!(
+        11 + 5 + 7,
+        score_guide(&rules, guide, Reading::Outcome).unwrap()
+    );
+
+    let err = "choice Rock 1 A X\nchoice Paper 2 B Y\noutcome lose 0 X"
+        .parse::<Rules>()
+        .unwrap_err();
+    assert_eq!("neither Rock nor Paper beats the other", err.to_string());
+
+    let err = "outcome lose 0 X\noutcome draw 3 X"
+        .parse::<Rules>()
+        .unwrap_err();
+    assert_eq!("line 2: draw's symbol is already used", err.to_string());
+    let err = "outcome lose 0 X\noutcome lose 3 Y"
+        .parse::<Rules>()
+        .unwrap_err();
+    assert_eq!("line 2: lose already has a score", err.to_string());
+}
 
-    let scores1 = games.clone().map(score_game_strategy_1);
-    let scores2 = games.map(score_game_strategy_2);
+#[test]
+fn every_mapping() {
+    let rules = include_str!("../inputs/day02-rules.txt")
+        .parse::<Rules>()
+        .unwrap();
+    let rounds = parse_guide(&rules, "A Y\nB X\nC Z").unwrap();
+    let mappings = mappings(&rules, &rounds);
+    // 3! ways to pick choices plus 3! ways to pick outcomes.
+    assert_eq!(12, mappings.len());
 
-    println!("Part A answer is: {:?}", scores1.sum::<u32>());
-    println!("Part B answer is: {:?}", scores2.sum::<u32>());
+    let scores = mappings
+        .iter()
+        .map(|m| (rules.describe(m), tally(&rules, &rounds, m).unwrap()))
+        .collect::<BTreeMap<_, _>>();
+    let tally = &scores["X=Rock Y=Paper Z=Scissors"];
+    assert_eq!(
+        (15, 1, 1, 1),
+        (tally.score, tally.wins, tally.draws, tally.losses)
+    );
+    // Always playing what beats them.
+    assert_eq!(24, scores["X=Scissors Y=Paper Z=Rock"].score);
+    assert_eq!(12, scores["X=lose Y=draw Z=win"].score);
 }
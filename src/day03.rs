@@ -1,64 +1,186 @@
+use std::fmt;
+
 use itertools::Itertools;
 
-fn get_priority(c: char) -> u32 {
+use crate::context::{Context, Part};
+
+#[derive(Debug, PartialEq)]
+enum OverlapError {
+    BadItem { line: usize, item: char },
+    Uneven { line: usize, compartments: usize },
+    Incomplete { line: usize, size: usize },
+    NoneShared { line: usize },
+    SeveralShared { line: usize, items: Vec<char> },
+}
+
+impl fmt::Display for OverlapError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            OverlapError::BadItem { line, item } => {
+                write!(f, "line {}: {:?} is not an item", line, item)
+            }
+            OverlapError::Uneven { line, compartments } => write!(
+                f,
+                "line {}: can't split into {} equal compartments",
+                line, compartments
+            ),
+            OverlapError::Incomplete { line, size } => {
+                write!(
+                    f,
+                    "line {}: fewer than {} rucksacks left for a group",
+                    line, size
+                )
+            }
+            OverlapError::NoneShared { line } => write!(f, "line {}: nothing is shared", line),
+            OverlapError::SeveralShared { line, items } => write!(
+                f,
+                "line {}: {} are all shared",
+                line,
+                items.iter().join(", ")
+            ),
+        }
+    }
+}
+
+fn priority(c: char) -> Option<u32> {
     if c.is_ascii_lowercase() {
-        return c as u32 - 'a' as u32 + 1;
+        Some(c as u32 - 'a' as u32 + 1)
     } else if c.is_ascii_uppercase() {
-        return c as u32 - 'A' as u32 + 26 + 1;
+        Some(c as u32 - 'A' as u32 + 26 + 1)
+    } else {
+        None
     }
-    panic!("Couldn't find priority for {}", c)
 }
 
-fn find_overlap(a: &str, b: &str) -> u32 {
-    let mut items: [bool; 52] = [false; 52];
+fn item(priority: u32) -> char {
+    (b" abcdefghijklmnopqrstuvwxyzABCDEFGHIJKLMNOPQRSTUVWXYZ"[priority as usize]) as char
+}
+
+// Bit n is set if the item with priority n is there.
+fn items(line: usize, s: &str) -> Result<u64, OverlapError> {
+    s.chars().try_fold(0, |set, c| match priority(c) {
+        Some(p) => Ok(set | 1 << p),
+        None => Err(OverlapError::BadItem { line, item: c }),
+    })
+}
 
-    for c in a.chars() {
-        items[get_priority(c) as usize - 1] = true;
+// The priorities of the items in every one of `sets`.
+fn common(line: usize, sets: &[&str]) -> Result<Vec<u32>, OverlapError> {
+    let shared = sets
+        .iter()
+        .try_fold(!0, |shared, s| Ok(shared & items(line, s)?))?;
+    Ok((1..=52).filter(|p| shared & 1 << p != 0).collect())
+}
+
+// The single item shared by a group, be it the compartments of one rucksack
+// or a group of rucksacks. `line` is where the group starts.
+fn shared_item(line: usize, sets: &[&str]) -> Result<u32, OverlapError> {
+    match common(line, sets)?[..] {
+        [] => Err(OverlapError::NoneShared { line }),
+        [p] => Ok(p),
+        ref several => Err(OverlapError::SeveralShared {
+            line,
+            items: several.iter().map(|&p| item(p)).collect(),
+        }),
     }
+}
 
-    for c in b.chars() {
-        if items[get_priority(c) as usize - 1] {
-            return get_priority(c);
-        }
+fn compartments(line: usize, rucksack: &str, n: usize) -> Result<Vec<&str>, OverlapError> {
+    // Items are all ASCII, so anything else can't be split on byte boundaries.
+    if let Some(c) = rucksack.chars().find(|c| !c.is_ascii()) {
+        return Err(OverlapError::BadItem { line, item: c });
     }
-    panic!("Couldn't find duplicate between {} and {}", a, b);
+    if n == 0 || !rucksack.len().is_multiple_of(n) {
+        return Err(OverlapError::Uneven {
+            line,
+            compartments: n,
+        });
+    }
+    let size = rucksack.len() / n;
+    Ok((0..n)
+        .map(|i| &rucksack[i * size..(i + 1) * size])
+        .collect())
 }
 
-// Laziness... Should really just write a proper intersect function.
-fn find_overlap_3(x: &str, y: &str, z: &str) -> u32 {
-    let mut items: [u8; 52] = [0; 52];
+fn compartment_total(rucksacks: &[&str], n: usize) -> Result<u32, OverlapError> {
+    rucksacks
+        .iter()
+        .enumerate()
+        .map(|(i, rucksack)| shared_item(i + 1, &compartments(i + 1, rucksack, n)?))
+        .sum()
+}
 
-    for c in x.chars().unique() {
-        items[get_priority(c) as usize - 1] += 1;
-    }
+fn badge_total(rucksacks: &[&str], size: usize) -> Result<u32, OverlapError> {
+    rucksacks
+        .chunks(size)
+        .enumerate()
+        .map(|(i, group)| {
+            let line = i * size + 1;
+            if group.len() < size {
+                return Err(OverlapError::Incomplete { line, size });
+            }
+            shared_item(line, group)
+        })
+        .sum()
+}
 
-    for c in y.chars().unique() {
-        items[get_priority(c) as usize - 1] += 1;
-    }
+pub fn day03(ctx: &Context) {
+    let rucksacks = ctx
+        .input(include_str!("../inputs/day03.txt"))
+        .lines()
+        .collect::<Vec<_>>();
 
-    for c in z.chars() {
-        if items[get_priority(c) as usize - 1] == 2 {
-            return get_priority(c);
+    if ctx.wants(Part::A) {
+        match compartment_total(&rucksacks, ctx.config("compartments", 2)) {
+            Ok(total) => ctx.answer(Part::A, total),
+            Err(e) => panic!("Bad rucksack: {}", e),
+        }
+    }
+    if ctx.wants(Part::B) {
+        match badge_total(&rucksacks, ctx.config("group_size", 3).max(1)) {
+            Ok(total) => ctx.answer(Part::B, total),
+            Err(e) => panic!("Bad group: {}", e),
         }
     }
-    panic!("Couldn't find duplicate between {}, {} and {}", x, y, z);
 }
 
-pub fn day03() {
-    let rucksacks = include_str!("../inputs/day03.txt").lines();
-    let priorities = rucksacks.clone().map(|rucksack| {
-        find_overlap(
-            &rucksack[..rucksack.len() / 2],
-            &rucksack[rucksack.len() / 2..],
-        )
-    });
-
-    let binding = rucksacks.collect::<Vec<_>>();
-    let badges = binding
-        .as_slice()
-        .chunks(3)
-        .map(|elem| find_overlap_3(elem[0], elem[1], elem[2]));
-
-    println!("Part A answer is: {:?}", priorities.sum::<u32>());
-    println!("Part B answer is: {:?}", badges.sum::<u32>());
+#[test]
+fn sample_input() {
+    let rucksacks = [
+        "vJrwpWtwJgWrhcsFMMfFFhFp",
+        "jqHRNqRjqzjGDLGLrsFMfFZSrLrFZsSL",
+        "PmmdzqPrVvPwwTWBwg",
+        "wMqvLMZHhHMvwLHjbvcjnnSBnvTQFn",
+        "ttgJtRGJQctTZtZT",
+        "CrZsJsPPZsGzwwsLwLmpwMDw",
+    ];
+    assert_eq!(Ok(157), compartment_total(&rucksacks, 2));
+    assert_eq!(Ok(70), badge_total(&rucksacks, 3));
+    assert_eq!(Ok(vec![6, 18, 19, 32, 39]), common(1, &rucksacks[..2]));
+
+    assert_eq!(
+        Err(OverlapError::SeveralShared {
+            line: 1,
+            items: vec!['f', 'r', 's', 'F', 'M'],
+        }),
+        badge_total(&rucksacks, 2)
+    );
+    assert_eq!(
+        Err(OverlapError::Incomplete { line: 4, size: 3 }),
+        badge_total(&rucksacks[..4], 3)
+    );
+    assert_eq!(
+        "line 3: can't split into 2 equal compartments",
+        compartment_total(&["aa", "bb", "abc"], 2)
+            .unwrap_err()
+            .to_string()
+    );
+    assert_eq!(
+        Err(OverlapError::BadItem { line: 2, item: '1' }),
+        compartment_total(&["aa", "a1"], 2)
+    );
+    assert_eq!(
+        Err(OverlapError::NoneShared { line: 1 }),
+        compartment_total(&["ab"], 2)
+    );
 }
@@ -1,32 +1,259 @@
+use std::{collections::BTreeMap, ops::RangeInclusive};
+
+use itertools::Itertools;
 use regex::Regex;
 
-fn parse(elem: &str) -> (u32, u32, u32, u32) {
-    let re = Regex::new(r"(\d*)-(\d*),(\d*)-(\d*)").unwrap();
-    let cap = re.captures_iter(elem).next().unwrap();
-    (
-        cap[1].parse::<u32>().unwrap(),
-        cap[2].parse::<u32>().unwrap(),
-        cap[3].parse::<u32>().unwrap(),
-        cap[4].parse::<u32>().unwrap(),
-    )
+use crate::{
+    context::{Context, Part},
+    interval::IntervalSet,
+};
+
+// Every "a-b" assignment on a line - two per line in the puzzle, but shift
+// plans can have any number.
+fn parse_line(elem: &str) -> Vec<RangeInclusive<i64>> {
+    let re = Regex::new(r"(\d+)-(\d+)").unwrap();
+    re.captures_iter(elem)
+        .map(|cap| cap[1].parse().unwrap()..=cap[2].parse().unwrap())
+        .collect()
+}
+
+fn parse(elem: &str) -> (IntervalSet, IntervalSet) {
+    parse_line(elem)
+        .into_iter()
+        .map(IntervalSet::from)
+        .collect_tuple()
+        .unwrap()
 }
 
 fn parse_and_subset(elem: &str) -> bool {
-    let (a_min, a_max, b_min, b_max) = parse(elem);
-    (a_min >= b_min && a_max <= b_max) || (a_min <= b_min && a_max >= b_max)
+    let (a, b) = parse(elem);
+    a.is_superset(&b) || b.is_superset(&a)
 }
 
 fn parse_and_overlap(elem: &str) -> bool {
-    let (a_min, a_max, b_min, b_max) = parse(elem);
-    (a_max >= b_min && a_min <= b_max) || (b_max >= a_min && b_min <= a_max)
+    let (a, b) = parse(elem);
+    !a.intersection(&b).is_empty()
+}
+
+#[derive(Debug)]
+struct Elf {
+    line: usize,
+    sections: RangeInclusive<i64>,
+}
+
+fn parse_elves(input: &str) -> Vec<Elf> {
+    input
+        .lines()
+        .enumerate()
+        .flat_map(|(i, line)| {
+            parse_line(line).into_iter().map(move |sections| Elf {
+                line: i + 1,
+                sections,
+            })
+        })
+        .filter(|elf| !elf.sections.is_empty())
+        .collect()
 }
 
-pub fn day04() {
-    let section_pairs = include_str!("../inputs/day04.txt").lines();
+// What the elves cover between them. Elves are referred to by their index.
+#[derive(Debug)]
+struct Coverage {
+    span: IntervalSet,
+    uncovered: IntervalSet,
+    most: usize,
+    busiest: IntervalSet,
+    // Each of these could be dropped on its own, though not necessarily all
+    // of them at once.
+    redundant: Vec<usize>,
+    minimum: Vec<usize>,
+}
+
+// How many elves cover each section, as (first section, elves) runs that last
+// until the next one starts.
+fn depths(elves: &[Elf]) -> Vec<(i64, usize)> {
+    let mut changes = BTreeMap::<i64, isize>::new();
+    for elf in elves {
+        *changes.entry(*elf.sections.start()).or_default() += 1;
+        *changes.entry(elf.sections.end() + 1).or_default() -= 1;
+    }
+    let mut depth = 0;
+    changes
+        .into_iter()
+        .map(|(section, change)| {
+            depth += change;
+            (section, depth as usize)
+        })
+        .collect()
+}
+
+// The fewest elves that between them still cover every covered section -
+// repeatedly take whichever elf reaches furthest past what's covered so far.
+fn minimum_cover(elves: &[Elf]) -> Vec<usize> {
+    let order = (0..elves.len())
+        .sorted_by_key(|&i| *elves[i].sections.start())
+        .collect::<Vec<_>>();
+    let mut chosen = vec![];
+    let mut covered_to: Option<i64> = None;
+    let mut i = 0;
+    while i < order.len() {
+        let next = match covered_to {
+            Some(end) => (end + 1).max(*elves[order[i]].sections.start()),
+            None => *elves[order[i]].sections.start(),
+        };
+        let mut furthest = order[i];
+        while i < order.len() && *elves[order[i]].sections.start() <= next {
+            if elves[order[i]].sections.end() > elves[furthest].sections.end() {
+                furthest = order[i];
+            }
+            i += 1;
+        }
+        let end = *elves[furthest].sections.end();
+        if end >= next {
+            chosen.push(furthest);
+            covered_to = Some(end);
+        }
+    }
+    chosen.sort_unstable();
+    chosen
+}
+
+fn coverage(elves: &[Elf]) -> Coverage {
+    let span = elves
+        .iter()
+        .map(|e| e.sections.clone())
+        .collect::<IntervalSet>();
+    let uncovered = span.gaps().collect();
+
+    let runs = depths(elves)
+        .windows(2)
+        .map(|w| (w[0].0..=w[1].0 - 1, w[0].1))
+        .collect::<Vec<_>>();
+    let most = runs.iter().map(|(_, depth)| *depth).max().unwrap_or(0);
+    let busiest = runs
+        .iter()
+        .filter(|(_, depth)| *depth == most)
+        .map(|(sections, _)| sections.clone())
+        .collect();
+    let shared = runs
+        .iter()
+        .filter(|(_, depth)| *depth >= 2)
+        .map(|(sections, _)| sections.clone())
+        .collect::<IntervalSet>();
+    let redundant = (0..elves.len())
+        .filter(|&i| shared.is_superset(&IntervalSet::from(elves[i].sections.clone())))
+        .collect();
+
+    Coverage {
+        span,
+        uncovered,
+        most,
+        busiest,
+        redundant,
+        minimum: minimum_cover(elves),
+    }
+}
+
+fn describe_sections(set: &IntervalSet) -> String {
+    if set.is_empty() {
+        return "none".to_string();
+    }
+    set.ranges()
+        .map(|r| {
+            if r.start() == r.end() {
+                r.start().to_string()
+            } else {
+                format!("{}-{}", r.start(), r.end())
+            }
+        })
+        .join(", ")
+}
+
+fn describe_elves(elves: &[Elf], chosen: &[usize]) -> String {
+    chosen
+        .iter()
+        .map(|&i| {
+            let sections = &elves[i].sections;
+            format!(
+                "{} (line {}, {}-{})",
+                i + 1,
+                elves[i].line,
+                sections.start(),
+                sections.end()
+            )
+        })
+        .join(", ")
+}
+
+fn report_coverage(ctx: &Context, input: &str) {
+    let elves = parse_elves(input);
+    let coverage = coverage(&elves);
+    ctx.diagnostic(format!(
+        "{} elves cover {} sections: {}",
+        elves.len(),
+        coverage.span.len(),
+        describe_sections(&coverage.span)
+    ));
+    ctx.diagnostic(format!(
+        "Covered by nobody: {}",
+        describe_sections(&coverage.uncovered)
+    ));
+    ctx.diagnostic(format!(
+        "Covered by the most elves ({}): {}",
+        coverage.most,
+        describe_sections(&coverage.busiest)
+    ));
+    ctx.diagnostic(format!(
+        "Redundant elves ({}): {}",
+        coverage.redundant.len(),
+        describe_elves(&elves, &coverage.redundant)
+    ));
+    ctx.diagnostic(format!(
+        "Fewest elves covering everything ({}): {}",
+        coverage.minimum.len(),
+        describe_elves(&elves, &coverage.minimum)
+    ));
+}
+
+pub fn day04(ctx: &Context) {
+    let input = ctx.input(include_str!("../inputs/day04.txt"));
+    if ctx.config("coverage", false) {
+        report_coverage(ctx, input);
+    }
+
+    let section_pairs = input.lines();
 
     let subset = section_pairs.clone().map(parse_and_subset);
     let overlap = section_pairs.map(parse_and_overlap);
 
-    println!("Part A answer is: {:?}", subset.filter(|p| *p).count());
-    println!("Part B answer is: {:?}", overlap.filter(|p| *p).count());
+    ctx.answer(Part::A, subset.filter(|p| *p).count());
+    ctx.answer(Part::B, overlap.filter(|p| *p).count());
+}
+
+#[test]
+fn sample_input() {
+    let pairs = [
+        "2-4,6-8", "2-3,4-5", "5-7,7-9", "2-8,3-7", "6-6,4-6", "2-6,4-8",
+    ];
+    assert_eq!(2, pairs.iter().filter(|p| parse_and_subset(p)).count());
+    assert_eq!(4, pairs.iter().filter(|p| parse_and_overlap(p)).count());
+}
+
+#[test]
+fn sample_coverage() {
+    let input = "2-4,6-8\n2-3,4-5\n5-7,7-9\n2-8,3-7\n6-6,4-6\n2-6,4-8\n20-22,21-21";
+    let elves = parse_elves(input);
+    let coverage = coverage(&elves);
+    assert_eq!("2-9, 20-22", describe_sections(&coverage.span));
+    assert_eq!("10-19", describe_sections(&coverage.uncovered));
+    assert_eq!(
+        (8, "6".to_string()),
+        (coverage.most, describe_sections(&coverage.busiest))
+    );
+    // Only elf 6 reaches section 9, and only elf 13 reaches 20 and 22.
+    let needed = [5, 12];
+    let redundant = (0..elves.len())
+        .filter(|i| !needed.contains(i))
+        .collect::<Vec<_>>();
+    assert_eq!(redundant, coverage.redundant);
+    assert_eq!(vec![5, 6, 12], coverage.minimum);
 }
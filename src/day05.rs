@@ -1,46 +1,518 @@
 use regex::Regex;
 
-pub fn day05() {
-    let mut stacks = vec![
-        vec!['L', 'N', 'W', 'T', 'D'],
-        vec!['C', 'P', 'H'],
-        vec!['W', 'P', 'H', 'N', 'D', 'G', 'M', 'J'],
-        vec!['C', 'W', 'S', 'N', 'T', 'Q', 'L'],
-        vec!['P', 'H', 'C', 'N'],
-        vec!['T', 'H', 'N', 'D', 'M', 'W', 'Q', 'B'],
-        vec!['M', 'B', 'R', 'J', 'G', 'S', 'L'],
-        vec!['Z', 'N', 'W', 'G', 'V', 'B', 'R', 'T'],
-        vec!['W', 'G', 'D', 'N', 'P', 'L'],
-    ];
+use crate::{
+    context::{Context, Part},
+    validate::Diagnostic,
+};
 
-    let moves = include_str!("../inputs/day05.txt").lines();
+// Splits the input at its first blank line, into the drawing and the moves.
+fn split_input(input: &str) -> Result<(Vec<&str>, Vec<&str>), Diagnostic> {
+    let lines = input.lines().collect::<Vec<_>>();
+    match lines.iter().position(|l| l.trim().is_empty()) {
+        Some(0) => Err(Diagnostic::at(0, "no drawing before the moves")),
+        Some(blank) => Ok((lines[..blank].to_vec(), lines[blank + 1..].to_vec())),
+        None => Err(Diagnostic::new(
+            "no blank line between the drawing and the moves",
+        )),
+    }
+}
 
-    for m in moves {
-        let re = Regex::new(r"move (\d*) from (\d*) to (\d*)").unwrap();
-        let cap = re.captures_iter(m).next().unwrap();
-        let count = cap[1].parse::<u32>().unwrap();
-        let from = cap[2].parse::<usize>().unwrap() - 1;
-        let to = cap[3].parse::<usize>().unwrap() - 1;
+// Reads a drawing like
+//
+//     [D]
+// [N] [C]
+// [Z] [M] [P]
+//  1   2   3
+//
+// into stacks, bottom crate first. Each crate's letter sits in the column of
+// the first digit of its stack's number, so any number of stacks works.
+fn parse_drawing(drawing: &[&str]) -> Result<Vec<Vec<char>>, Diagnostic> {
+    let (base, rows) = drawing.split_last().unwrap();
+    let base_index = rows.len();
 
-        // Part 1
-        // for _ in 0..count {
-        //     let elem = stacks[from].pop().unwrap();
-        //     stacks[to].push(elem);
-        // }
+    // Column of each stack's number, checking they go 1, 2, 3...
+    let mut columns = vec![];
+    let re = Regex::new(r"\S+").unwrap();
+    for m in re.find_iter(base) {
+        if m.as_str().parse() != Ok(columns.len() + 1) {
+            return Err(Diagnostic::at(
+                base_index,
+                format!(
+                    "expected stack {}, found {:?}",
+                    columns.len() + 1,
+                    m.as_str()
+                ),
+            ));
+        }
+        columns.push(m.start());
+    }
+    if columns.is_empty() {
+        return Err(Diagnostic::at(base_index, "no stack numbers"));
+    }
 
-        // Part 2
-        let mut hold = vec![];
-        for _ in 0..count {
-            let elem = stacks[from].pop().unwrap();
-            hold.push(elem);
+    let mut stacks = vec![vec![]; columns.len()];
+    for (i, row) in rows.iter().enumerate().rev() {
+        let row = row.chars().collect::<Vec<_>>();
+        let mut column = 0;
+        while column < row.len() {
+            match row[column] {
+                ' ' => column += 1,
+                '[' => {
+                    let letter = match row.get(column + 1..column + 3) {
+                        Some([letter, ']']) if letter.is_ascii_alphabetic() => *letter,
+                        _ => {
+                            return Err(Diagnostic::at(
+                                i,
+                                format!("broken crate at column {}", column + 1),
+                            ))
+                        }
+                    };
+                    let stack = columns
+                        .iter()
+                        .position(|&c| c == column + 1)
+                        .ok_or_else(|| {
+                            Diagnostic::at(
+                                i,
+                                format!("crate at column {} isn't above a stack", column + 1),
+                            )
+                        })?;
+                    if stacks[stack].len() != base_index - 1 - i {
+                        return Err(Diagnostic::at(
+                            i,
+                            format!(
+                                "crate {} in stack {} has nothing under it",
+                                letter,
+                                stack + 1
+                            ),
+                        ));
+                    }
+                    stacks[stack].push(letter);
+                    column += 3;
+                }
+                c => {
+                    return Err(Diagnostic::at(
+                        i,
+                        format!("unexpected {:?} at column {}", c, column + 1),
+                    ))
+                }
+            }
         }
-        for _ in 0..count {
-            let elem = hold.pop().unwrap();
-            stacks[to].push(elem);
+    }
+    Ok(stacks)
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+struct Move {
+    count: usize,
+    // Stack indices, counting from zero.
+    from: usize,
+    to: usize,
+}
+
+// Parses the moves, checking each one only takes crates that are there.
+// `first_index` is the line the moves start on.
+fn parse_moves(
+    lines: &[&str],
+    first_index: usize,
+    stacks: &[Vec<char>],
+) -> Result<Vec<Move>, Diagnostic> {
+    let re = Regex::new(r"^move (\d+) from (\d+) to (\d+)$").unwrap();
+    let mut heights = stacks.iter().map(|s| s.len()).collect::<Vec<_>>();
+    let mut moves = vec![];
+    for (i, line) in lines
+        .iter()
+        .enumerate()
+        .filter(|(_, l)| !l.trim().is_empty())
+    {
+        let index = first_index + i;
+        let cap = re
+            .captures(line.trim_end())
+            .ok_or_else(|| Diagnostic::at(index, format!("{:?} is not a move", line)))?;
+        let stack = |n: &str| match n.parse::<usize>() {
+            Ok(n) if (1..=stacks.len()).contains(&n) => Ok(n - 1),
+            _ => Err(Diagnostic::at(index, format!("there is no stack {}", n))),
+        };
+        let m = Move {
+            count: cap[1].parse().unwrap(),
+            from: stack(&cap[2])?,
+            to: stack(&cap[3])?,
+        };
+        if m.count > heights[m.from] {
+            return Err(Diagnostic::at(
+                index,
+                format!("stack {} only has {} crates", m.from + 1, heights[m.from]),
+            ));
+        }
+        heights[m.from] -= m.count;
+        heights[m.to] += m.count;
+        moves.push(m);
+    }
+    Ok(moves)
+}
+
+fn parse_input(input: &str) -> Result<(Vec<Vec<char>>, Vec<Move>), Diagnostic> {
+    let (drawing, moves) = split_input(input)?;
+    let stacks = parse_drawing(&drawing)?;
+    let moves = parse_moves(&moves, drawing.len() + 1, &stacks)?;
+    Ok((stacks, moves))
+}
+
+trait Crane {
+    fn name(&self) -> String;
+
+    // How many crates it can lift at once. Crates lifted together keep their
+    // order.
+    fn capacity(&self) -> usize;
+
+    fn apply(&self, stacks: &mut [Vec<char>], m: Move) {
+        // A crane that lifts nothing would never finish a move.
+        assert!(self.capacity() > 0, "{} can't lift anything", self.name());
+        let mut left = m.count;
+        while left > 0 {
+            let lift = left.min(self.capacity());
+            let from = &mut stacks[m.from];
+            let load = from.split_off(from.len() - lift);
+            stacks[m.to].extend(load);
+            left -= lift;
         }
     }
-    println!(
-        "{:?}",
-        stacks.iter().map(|v| v.last().unwrap()).collect::<Vec<_>>()
+}
+
+// Part A's crane, which moves one crate at a time.
+struct CrateMover9000;
+
+impl Crane for CrateMover9000 {
+    fn name(&self) -> String {
+        "CrateMover 9000".to_string()
+    }
+
+    fn capacity(&self) -> usize {
+        1
+    }
+}
+
+// Part B's crane, which moves any number of crates in one go.
+struct CrateMover9001;
+
+impl Crane for CrateMover9001 {
+    fn name(&self) -> String {
+        "CrateMover 9001".to_string()
+    }
+
+    fn capacity(&self) -> usize {
+        usize::MAX
+    }
+}
+
+// Somewhere in between - at most this many crates at a time.
+struct LimitedCrane(usize);
+
+impl Crane for LimitedCrane {
+    fn name(&self) -> String {
+        format!("Crane lifting up to {}", self.0)
+    }
+
+    fn capacity(&self) -> usize {
+        self.0
+    }
+}
+
+fn rearrange(crane: &dyn Crane, stacks: &[Vec<char>], moves: &[Move]) -> Vec<Vec<char>> {
+    let mut stacks = stacks.to_vec();
+    for &m in moves {
+        crane.apply(&mut stacks, m);
+    }
+    stacks
+}
+
+// Steps through a rearrangement in either direction. Each move remembers the
+// crates it lifted, so undoing it puts them back exactly whatever the crane.
+struct Replay<'a> {
+    crane: &'a dyn Crane,
+    moves: &'a [Move],
+    stacks: Vec<Vec<char>>,
+    lifted: Vec<Vec<char>>,
+}
+
+impl<'a> Replay<'a> {
+    fn new(crane: &'a dyn Crane, stacks: &[Vec<char>], moves: &'a [Move]) -> Replay<'a> {
+        Replay {
+            crane,
+            moves,
+            stacks: stacks.to_vec(),
+            lifted: vec![],
+        }
+    }
+
+    // How many moves have been made.
+    fn step(&self) -> usize {
+        self.lifted.len()
+    }
+
+    fn forward(&mut self) -> bool {
+        let Some(&m) = self.moves.get(self.step()) else {
+            return false;
+        };
+        let from = &self.stacks[m.from];
+        self.lifted.push(from[from.len() - m.count..].to_vec());
+        self.crane.apply(&mut self.stacks, m);
+        true
+    }
+
+    fn back(&mut self) -> bool {
+        let Some(lifted) = self.lifted.pop() else {
+            return false;
+        };
+        let m = self.moves[self.step()];
+        let to = &mut self.stacks[m.to];
+        to.truncate(to.len() - m.count);
+        self.stacks[m.from].extend(lifted);
+        true
+    }
+
+    fn seek(&mut self, step: usize) {
+        while self.step() < step && self.forward() {}
+        while self.step() > step && self.back() {}
+    }
+
+    // How many moves it takes before `item` is on top of a stack, if it ever
+    // is. Any crate with that letter counts.
+    fn first_on_top(&mut self, item: char) -> Option<usize> {
+        self.seek(0);
+        loop {
+            if tops(&self.stacks).contains(item) {
+                return Some(self.step());
+            }
+            if !self.forward() {
+                return None;
+            }
+        }
+    }
+
+    fn show(&self, ctx: &Context) {
+        let when = match self.step() {
+            0 => "at the start".to_string(),
+            step => format!("after move {}", step),
+        };
+        ctx.diagnostic(format!(
+            "{} {}:\n{}\n",
+            self.crane.name(),
+            when,
+            draw(&self.stacks)
+        ));
+    }
+}
+
+// Optional extras: when a crate first surfaces ("find_crate"), and the stacks
+// after a given move or every one of them ("show_move").
+fn trace(ctx: &Context, crane: &dyn Crane, stacks: &[Vec<char>], moves: &[Move]) {
+    let mut replay = Replay::new(crane, stacks, moves);
+    let item = ctx.config("find_crate", ' ');
+    if item != ' ' {
+        let name = crane.name();
+        match replay.first_on_top(item) {
+            Some(0) => ctx.diagnostic(format!("{}: {} starts on top", name, item)),
+            Some(step) => ctx.diagnostic(format!(
+                "{}: {} first reaches the top after move {}",
+                name, item, step
+            )),
+            None => ctx.diagnostic(format!("{}: {} never reaches the top", name, item)),
+        }
+    }
+
+    match ctx.config("show_move", String::new()).as_str() {
+        "" => {}
+        "all" => {
+            replay.seek(0);
+            replay.show(ctx);
+            while replay.forward() {
+                replay.show(ctx);
+            }
+        }
+        step => {
+            let step = step
+                .parse()
+                .unwrap_or_else(|_| panic!("Bad value for show_move: {}", step));
+            replay.seek(step);
+            replay.show(ctx);
+        }
+    }
+}
+
+// The crate on top of each stack, or a space for an empty one.
+fn tops(stacks: &[Vec<char>]) -> String {
+    stacks
+        .iter()
+        .map(|s| s.last().copied().unwrap_or(' '))
+        .collect()
+}
+
+// The inverse of `parse_drawing`, in the puzzle's layout.
+pub(crate) fn draw(stacks: &[Vec<char>]) -> String {
+    let height = stacks.iter().map(|s| s.len()).max().unwrap_or(0);
+    let mut lines = (0..height)
+        .rev()
+        .map(|level| {
+            let row = stacks
+                .iter()
+                .map(|s| {
+                    s.get(level)
+                        .map_or("   ".to_string(), |c| format!("[{}]", c))
+                })
+                .collect::<Vec<_>>();
+            row.join(" ").trim_end().to_string()
+        })
+        .collect::<Vec<_>>();
+    lines.push(
+        (1..=stacks.len())
+            .map(|n| format!(" {:<3}", n))
+            .collect::<String>()
+            .trim_end()
+            .to_string(),
+    );
+    lines.join("\n")
+}
+
+// The drawing's problems, if it has any.
+pub(crate) fn validate(input: &str) -> Vec<Diagnostic> {
+    parse_input(input).err().into_iter().collect()
+}
+
+pub fn day05(ctx: &Context) {
+    let input = ctx.input(include_str!("../inputs/day05.txt"));
+    let (stacks, moves) = parse_input(input).unwrap_or_else(|e| panic!("Bad input: {}", e));
+
+    for (part, crane) in [
+        (Part::A, &CrateMover9000 as &dyn Crane),
+        (Part::B, &CrateMover9001),
+    ] {
+        if ctx.wants(part) {
+            trace(ctx, crane, &stacks, &moves);
+            ctx.answer(part, tops(&rearrange(crane, &stacks, &moves)));
+        }
+    }
+
+    let capacity = ctx.config("capacity", String::new());
+    if !capacity.is_empty() {
+        let crane = match capacity.parse() {
+            Ok(capacity) if capacity > 0 => LimitedCrane(capacity),
+            _ => panic!("Bad value for capacity: {}", capacity),
+        };
+        let stacks = rearrange(&crane, &stacks, &moves);
+        ctx.diagnostic(format!("{}: {}", crane.name(), tops(&stacks)));
+    }
+}
+
+#[test]
+fn sample_drawing() {
+    let input = "    [D]    \n[N] [C]    \n[Z] [M] [P]\n 1   2   3 \n\nmove 1 from 2 to 1";
+    let (stacks, moves) = parse_input(input).unwrap();
+    assert_eq!(vec![vec!['Z', 'N'], vec!['M', 'C', 'D'], vec!['P']], stacks);
+    assert_eq!(
+        vec![Move {
+            count: 1,
+            from: 1,
+            to: 0
+        }],
+        moves
+    );
+    let drawing = input.lines().take(4).map(str::trim_end).collect::<Vec<_>>();
+    assert_eq!(drawing.join("\n"), draw(&stacks));
+
+    // Ragged lines and ten stacks.
+    let stacks = (0..10).map(|i| vec!['A'; i % 3]).collect::<Vec<_>>();
+    assert_eq!(stacks, parse_input(&(draw(&stacks) + "\n\n")).unwrap().0);
+
+    let errors = [
+        (
+            "[Z] [M]\n 1   3\n\n",
+            "line 2: expected stack 2, found \"3\"",
+        ),
+        (
+            "[Z]     [P]\n 1   2\n\n",
+            "line 1: crate at column 9 isn't above a stack",
+        ),
+        (
+            "    [D]\n[Z]\n 1   2\n\n",
+            "line 1: crate D in stack 2 has nothing under it",
+        ),
+        ("[Z] [M\n 1   2\n\n", "line 1: broken crate at column 5"),
+        (
+            "[Z]\n 1\nmove 1 from 1 to 1",
+            "no blank line between the drawing and the moves",
+        ),
+    ];
+    for (input, error) in errors {
+        assert_eq!(error, parse_input(input).unwrap_err().to_string());
+    }
+}
+
+#[test]
+fn sample_input() {
+    let input = "    [D]\n[N] [C]\n[Z] [M] [P]\n 1   2   3\n
+move 1 from 2 to 1
+move 3 from 1 to 3
+move 2 from 2 to 1
+move 1 from 1 to 2";
+    let (stacks, moves) = parse_input(input).unwrap();
+    assert_eq!("CMZ", tops(&rearrange(&CrateMover9000, &stacks, &moves)));
+    assert_eq!("MCD", tops(&rearrange(&CrateMover9001, &stacks, &moves)));
+
+    // Moving Z, N, D two at a time: N and D are lifted together, then Z.
+    let stacks = rearrange(&LimitedCrane(2), &stacks, &moves[..2]);
+    assert_eq!(vec!['P', 'N', 'D', 'Z'], stacks[2]);
+}
+
+#[test]
+fn replay_both_ways() {
+    let input = "    [D]\n[N] [C]\n[Z] [M] [P]\n 1   2   3\n
+move 1 from 2 to 1
+move 3 from 1 to 3
+move 2 from 2 to 1
+move 1 from 1 to 2";
+    let (stacks, moves) = parse_input(input).unwrap();
+
+    let crane = LimitedCrane(2);
+    let mut replay = Replay::new(&crane, &stacks, &moves);
+    replay.seek(moves.len());
+    assert!(!replay.forward());
+    replay.seek(2);
+    assert_eq!(rearrange(&crane, &stacks, &moves[..2]), replay.stacks);
+    replay.seek(0);
+    assert_eq!(stacks, replay.stacks);
+    assert!(!replay.back());
+
+    // One crate at a time leaves Z on top of stack 3; all at once buries it.
+    assert_eq!(
+        Some(2),
+        Replay::new(&CrateMover9000, &stacks, &moves).first_on_top('Z')
+    );
+    assert_eq!(
+        None,
+        Replay::new(&CrateMover9001, &stacks, &moves).first_on_top('Z')
+    );
+    assert_eq!(
+        Some(0),
+        Replay::new(&CrateMover9001, &stacks, &moves).first_on_top('D')
+    );
+}
+
+#[test]
+fn trace_from_settings() {
+    let (mut ctx, answers, diagnostics) = Context::captured();
+    ctx.input = Some(
+        "    [D]\n[N] [C]\n[Z] [M] [P]\n 1   2   3\n\nmove 1 from 2 to 1\nmove 3 from 1 to 3"
+            .to_string(),
+    );
+    ctx.part = Some(Part::A);
+    ctx.set("find_crate", "Z");
+    ctx.set("show_move", "1");
+    day05(&ctx);
+    assert_eq!("Part A is:  CZ\n", answers.contents());
+    assert_eq!(
+        "CrateMover 9000: Z first reaches the top after move 2\n\
+         CrateMover 9000 after move 1:\n\
+         [D]\n[N] [C]\n[Z] [M] [P]\n 1   2   3\n\n",
+        diagnostics.contents()
     );
 }
@@ -1,16 +1,159 @@
-use itertools::Itertools;
-
-fn unique_chars(stream: Vec<char>, count: usize) -> usize {
-    stream
-        .windows(count)
-        .position(|cs| cs.iter().unique().count() == cs.len())
-        .unwrap()
-        + count
+use std::{
+    collections::VecDeque,
+    io::{self, Read},
+};
+
+use crate::context::{Context, Part};
+
+// The last `size` bytes of the stream, as a count of each byte value.
+struct Window {
+    size: usize,
+    counts: [usize; 256],
+    // How many byte values appear more than once.
+    repeated: usize,
+}
+
+// Looks for markers - runs of bytes that are all different - of several sizes
+// at once. Each byte costs the same however big the windows are, and only the
+// largest window's worth of the stream is ever kept.
+struct Scanner {
+    windows: Vec<Window>,
+    recent: VecDeque<u8>,
+    largest: usize,
+    position: usize,
+}
+
+impl Scanner {
+    fn new(sizes: &[usize]) -> Scanner {
+        let windows = sizes
+            .iter()
+            .map(|&size| Window {
+                size,
+                counts: [0; 256],
+                repeated: 0,
+            })
+            .collect();
+        Scanner {
+            windows,
+            recent: VecDeque::new(),
+            largest: sizes.iter().copied().max().unwrap_or(0),
+            position: 0,
+        }
+    }
+
+    // Calls `found(size, position)` for each window that ends in a marker now
+    // `byte` has arrived. Positions count bytes from one, as in the puzzle.
+    fn push(&mut self, byte: u8, mut found: impl FnMut(usize, usize)) {
+        self.position += 1;
+        for window in &mut self.windows {
+            window.counts[byte as usize] += 1;
+            if window.counts[byte as usize] == 2 {
+                window.repeated += 1;
+            }
+            if self.recent.len() >= window.size {
+                let old = self.recent[self.recent.len() - window.size] as usize;
+                window.counts[old] -= 1;
+                if window.counts[old] == 1 {
+                    window.repeated -= 1;
+                }
+            }
+            if self.position >= window.size && window.repeated == 0 {
+                found(window.size, self.position);
+            }
+        }
+
+        self.recent.push_back(byte);
+        if self.recent.len() > self.largest {
+            self.recent.pop_front();
+        }
+    }
+
+    // Feeds in a whole stream a chunk at a time. Line endings aren't part of
+    // the datastream, so are skipped.
+    fn scan(
+        &mut self,
+        mut stream: impl Read,
+        mut found: impl FnMut(usize, usize),
+    ) -> io::Result<()> {
+        let mut buffer = [0; 64 * 1024];
+        loop {
+            let read = match stream.read(&mut buffer) {
+                Ok(0) => return Ok(()),
+                Ok(read) => read,
+                Err(e) if e.kind() == io::ErrorKind::Interrupted => continue,
+                Err(e) => return Err(e),
+            };
+            for &byte in buffer[..read].iter().filter(|&&b| b != b'\n' && b != b'\r') {
+                self.push(byte, &mut found);
+            }
+        }
+    }
+}
+
+// Every marker of each size, in the order of `sizes`.
+#[cfg(test)]
+fn markers(stream: impl Read, sizes: &[usize]) -> io::Result<Vec<Vec<usize>>> {
+    let mut markers = vec![vec![]; sizes.len()];
+    Scanner::new(sizes).scan(stream, |size, position| {
+        let i = sizes.iter().position(|&s| s == size).unwrap();
+        markers[i].push(position);
+    })?;
+    Ok(markers)
+}
+
+pub fn day06(ctx: &Context) {
+    let sizes = [4, 14];
+    let every_marker = ctx.config("every_marker", false);
+    let mut first = [None; 2];
+    let mut counts = [0; 2];
+    let stream = ctx.reader(include_str!("../inputs/day06.txt"));
+    Scanner::new(&sizes)
+        .scan(stream, |size, position| {
+            let i = sizes.iter().position(|&s| s == size).unwrap();
+            first[i] = first[i].or(Some(position));
+            if every_marker {
+                ctx.diagnostic(format!("Marker of {} characters at {}", size, position));
+            }
+            counts[i] += 1;
+        })
+        .unwrap();
+
+    for (i, &part) in [Part::A, Part::B].iter().enumerate() {
+        ctx.diagnostic(format!("{} markers of {} characters", counts[i], sizes[i]));
+        match first[i] {
+            Some(position) => ctx.answer(part, position),
+            None => ctx.diagnostic(format!("No marker of {} characters", sizes[i])),
+        }
+    }
 }
 
-pub fn day06() {
-    let stream: Vec<char> = include_str!("../inputs/day06.txt").chars().collect();
+#[test]
+fn sample_input() {
+    let samples = [
+        ("mjqjpqmgbljsphdztnvjfqwrcgsmlb", 7, 19),
+        ("bvwbjplbgvbhsrlpgdmjqwftvncz", 5, 23),
+        ("nppdvjthqldpwncqszvftbrmjlhg", 6, 23),
+        ("nznrnfrfntjfmvfwmzdfjlvtqnbhcprsg", 10, 29),
+        ("zcfzfwzzqfrljwzlrfnpqdbhtmscgvjw", 11, 26),
+    ];
+    for (stream, a, b) in samples {
+        let found = markers(stream.as_bytes(), &[4, 14]).unwrap();
+        assert_eq!((Some(&a), Some(&b)), (found[0].first(), found[1].first()));
 
-    println!("Part A answer is: {:?}", unique_chars(stream.clone(), 4));
-    println!("Part B answer is: {:?}", unique_chars(stream, 14));
+        // Every marker, checked against looking at each window in turn.
+        for (size, found) in [1, 4, 14, 40]
+            .iter()
+            .copied()
+            .zip(markers(stream.as_bytes(), &[1, 4, 14, 40]).unwrap())
+        {
+            let naive = stream
+                .as_bytes()
+                .windows(size)
+                .enumerate()
+                .filter(|(_, w)| (1..w.len()).all(|i| !w[..i].contains(&w[i])))
+                .map(|(i, _)| i + size)
+                .collect::<Vec<_>>();
+            assert_eq!(naive, found, "{} with windows of {}", stream, size);
+        }
+    }
 }
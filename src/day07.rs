@@ -1,99 +1,682 @@
-use std::{collections::HashMap, str::Split};
+use std::{
+    collections::{BTreeMap, HashMap},
+    env, fs, io,
+    path::{Path, PathBuf},
+};
 
-use regex::Regex;
+use structopt::StructOpt;
 
-fn calculate_size(lookup: &HashMap<String, (Vec<String>, u32)>, key: &String) -> u32 {
-    let mut size = 0;
-    let (children, local_size) = lookup.get(key).unwrap();
-    for c in children {
-        size += calculate_size(lookup, c)
-    }
-    size + local_size
+use crate::{
+    context::{Context, Part},
+    validate::Diagnostic,
+};
+
+#[derive(Debug, Default, PartialEq, Eq)]
+pub struct Dir {
+    dirs: BTreeMap<String, Dir>,
+    files: BTreeMap<String, u64>,
+}
+
+// A file or directory turned up by `find`, with its total size.
+#[derive(Debug, PartialEq, Eq)]
+pub struct Entry {
+    pub path: String,
+    pub size: u64,
+    pub is_dir: bool,
 }
 
-fn parse_input(input: &str) -> Split<&str> {
-    input.split("\n$ ")
+fn join(parent: &str, name: &str) -> String {
+    format!("{}/{}", parent.trim_end_matches('/'), name)
 }
 
-fn solve(commands: Split<&str>) {
-    let cd_re = Regex::new(r"cd (.*)").unwrap();
-    let ls_re = Regex::new(r"ls\n(.*)").unwrap();
-    let dir_re = Regex::new(r"dir (.*)").unwrap();
-    let file_re = Regex::new(r"(\d*) (.*)").unwrap();
+// A line of a terminal transcript.
+enum Line<'a> {
+    Cd(&'a str),
+    Ls,
+    // Part of what `ls` printed - a size for files, nothing for directories.
+    Entry { name: &'a str, size: Option<u64> },
+}
 
-    let mut lookup = HashMap::new();
-    // Current working directory - not part of the tree.
-    let mut current_dir: Vec<String> = vec![];
+fn parse_line(index: usize, line: &str) -> Result<Line<'_>, Diagnostic> {
+    if let Some(command) = line.strip_prefix("$ ") {
+        return match command.split_once(' ') {
+            Some(("cd", dir)) if !dir.is_empty() => Ok(Line::Cd(dir)),
+            None if command == "ls" => Ok(Line::Ls),
+            _ => Err(Diagnostic::at(
+                index,
+                format!("unknown command {:?}", command),
+            )),
+        };
+    }
+    let (name, size) = match line.split_once(' ') {
+        Some(("dir", name)) => (name, None),
+        Some((size, name)) => match size.parse() {
+            Ok(size) => (name, Some(size)),
+            Err(_) => return Err(Diagnostic::at(index, format!("{:?} is not a size", size))),
+        },
+        None => {
+            return Err(Diagnostic::at(
+                index,
+                format!("{:?} is neither a command nor a listing", line),
+            ))
+        }
+    };
+    if name.is_empty() || name == "." || name == ".." || name.contains('/') {
+        return Err(Diagnostic::at(index, format!("{:?} can't be a name", name)));
+    }
+    Ok(Line::Entry { name, size })
+}
+
+fn path(cwd: &[String]) -> String {
+    format!("/{}", cwd.join("/"))
+}
 
-    for command in commands {
-        if cd_re.is_match(command) {
-            let cap = cd_re.captures(command).unwrap();
-            match &cap[1] {
-                "/" => current_dir = vec![],
-                ".." => {
-                    current_dir.pop();
+impl Dir {
+    // Rebuilds the filesystem a terminal transcript explored. The first time a
+    // directory is listed fills it in; listing it again has to agree.
+    pub fn parse(transcript: &str) -> Result<Dir, Diagnostic> {
+        let mut root = Dir::default();
+        let mut cwd: Vec<String> = vec![];
+        // Where each directory was first listed.
+        let mut listed = HashMap::new();
+        // What the `ls` on the given line has printed so far.
+        let mut listing: Option<(usize, Dir)> = None;
+
+        for (index, line) in transcript.lines().enumerate() {
+            let line = parse_line(index, line)?;
+            if !matches!(line, Line::Entry { .. }) {
+                if let Some((ls, entries)) = listing.take() {
+                    root.list(&cwd, ls, entries, &mut listed)?;
+                }
+            }
+            match line {
+                Line::Entry { name, size } => {
+                    let (_, entries) = listing
+                        .as_mut()
+                        .ok_or_else(|| Diagnostic::at(index, "output without an ls"))?;
+                    if entries.dirs.contains_key(name) || entries.files.contains_key(name) {
+                        return Err(Diagnostic::at(index, format!("{} is listed twice", name)));
+                    }
+                    match size {
+                        Some(size) => {
+                            entries.files.insert(name.to_string(), size);
+                        }
+                        None => {
+                            entries.dirs.insert(name.to_string(), Dir::default());
+                        }
+                    }
+                }
+                Line::Cd("/") => cwd.clear(),
+                Line::Cd("..") => {
+                    if cwd.pop().is_none() {
+                        return Err(Diagnostic::at(index, "cd .. from the root"));
+                    }
+                }
+                Line::Cd(name) => {
+                    let dir = root.dir_mut(&cwd);
+                    if dir.files.contains_key(name) {
+                        return Err(Diagnostic::at(index, format!("{} is a file", name)));
+                    }
+                    if !dir.dirs.contains_key(name) {
+                        return Err(Diagnostic::at(
+                            index,
+                            format!("{} hasn't been listed in {}", name, path(&cwd)),
+                        ));
+                    }
+                    cwd.push(name.to_string());
                 }
-                dir => {
-                    current_dir.push(dir.to_string());
+                Line::Ls => listing = Some((index, Dir::default())),
+            }
+        }
+        if let Some((ls, entries)) = listing {
+            root.list(&cwd, ls, entries, &mut listed)?;
+        }
+        Ok(root)
+    }
+
+    // Fills in the directory at `cwd` from what the `ls` on line `ls` printed.
+    fn list(
+        &mut self,
+        cwd: &[String],
+        ls: usize,
+        entries: Dir,
+        listed: &mut HashMap<Vec<String>, usize>,
+    ) -> Result<(), Diagnostic> {
+        let dir = self.dir_mut(cwd);
+        match listed.get(cwd) {
+            Some(&first) => {
+                if dir.files != entries.files || !dir.dirs.keys().eq(entries.dirs.keys()) {
+                    return Err(Diagnostic::at(
+                        ls,
+                        format!(
+                            "{} doesn't match how it was listed on line {}",
+                            path(cwd),
+                            first + 1
+                        ),
+                    ));
                 }
             }
-        } else if ls_re.is_match(command) {
-            let mut fs = vec![];
-            let mut local_size = 0;
-            for f in command.lines() {
-                if dir_re.is_match(f) {
-                    let dir = dir_re.captures(f).unwrap()[1].to_string();
-                    let mut entry = current_dir.clone();
-                    entry.push(dir.to_string());
-                    fs.push(format!("/{}", entry.join("/")));
-                } else if file_re.is_match(f) {
-                    let file = file_re.captures(f).unwrap();
-                    let size = file[1].parse::<u32>().unwrap();
-                    local_size += size;
+            None => {
+                listed.insert(cwd.to_vec(), ls);
+                *dir = entries;
+            }
+        }
+        Ok(())
+    }
+
+    // The directory at `path` below this one, made if it isn't there yet.
+    fn dir_mut(&mut self, path: &[String]) -> &mut Dir {
+        path.iter()
+            .fold(self, |dir, name| dir.dirs.entry(name.clone()).or_default())
+    }
+
+    pub fn size(&self) -> u64 {
+        self.files.values().sum::<u64>() + self.dirs.values().map(Dir::size).sum::<u64>()
+    }
+
+    // Adds (path, total size) for every directory to `sizes`, children before
+    // their parents like `du`, and returns this directory's size.
+    fn sizes(&self, path: &str, sizes: &mut Vec<(String, u64)>) -> u64 {
+        let mut size = self.files.values().sum::<u64>();
+        for (name, dir) in &self.dirs {
+            size += dir.sizes(&join(path, name), sizes);
+        }
+        sizes.push((path.to_string(), size));
+        size
+    }
+
+    pub fn du(&self) -> Vec<(String, u64)> {
+        let mut sizes = vec![];
+        self.sizes("/", &mut sizes);
+        sizes
+    }
+
+    // Every directory and file between the two sizes, inclusive, in `du` order
+    // with each directory's files just before it.
+    pub fn find(&self, min_size: Option<u64>, max_size: Option<u64>) -> Vec<Entry> {
+        let wanted = |size: u64| {
+            min_size.is_none_or(|min| size >= min) && max_size.is_none_or(|max| size <= max)
+        };
+        let mut found = vec![];
+        self.find_in("/", &wanted, &mut found);
+        found
+    }
+
+    fn find_in(&self, path: &str, wanted: &dyn Fn(u64) -> bool, found: &mut Vec<Entry>) -> u64 {
+        let mut size = 0;
+        for (name, dir) in &self.dirs {
+            size += dir.find_in(&join(path, name), wanted, found);
+        }
+        for (name, &file_size) in &self.files {
+            size += file_size;
+            if wanted(file_size) {
+                found.push(Entry {
+                    path: join(path, name),
+                    size: file_size,
+                    is_dir: false,
+                });
+            }
+        }
+        if wanted(size) {
+            found.push(Entry {
+                path: path.to_string(),
+                size,
+                is_dir: true,
+            });
+        }
+        size
+    }
+
+    // Reads a real directory, without following symlinks or counting anything
+    // that's neither a file nor a directory.
+    pub fn read(path: &Path) -> io::Result<Dir> {
+        let mut dir = Dir::default();
+        for entry in fs::read_dir(path)? {
+            let entry = entry?;
+            let name = entry.file_name().into_string().map_err(|name| {
+                io::Error::new(
+                    io::ErrorKind::InvalidData,
+                    format!("{:?} isn't valid UTF-8", name),
+                )
+            })?;
+            let kind = entry.file_type()?;
+            if kind.is_dir() {
+                dir.dirs.insert(name, Dir::read(&entry.path())?);
+            } else if kind.is_file() {
+                dir.files.insert(name, entry.metadata()?.len());
+            }
+        }
+        Ok(dir)
+    }
+
+    // Makes the directory for real under `path`, which mustn't exist yet. Files
+    // are sparse, so take up next to no space whatever their size.
+    pub fn materialize(&self, path: &Path) -> io::Result<()> {
+        fs::create_dir(path)?;
+        for (name, &size) in &self.files {
+            fs::File::create(path.join(name))?.set_len(size)?;
+        }
+        for (name, dir) in &self.dirs {
+            dir.materialize(&path.join(name))?;
+        }
+        Ok(())
+    }
+
+    // A terminal session that explores the whole directory, for `parse` to
+    // read back.
+    pub fn transcript(&self) -> String {
+        let mut lines = vec!["$ cd /".to_string()];
+        self.transcript_lines(&mut lines);
+        lines.join("\n")
+    }
+
+    fn transcript_lines(&self, lines: &mut Vec<String>) {
+        lines.push("$ ls".to_string());
+        lines.extend(self.dirs.keys().map(|name| format!("dir {}", name)));
+        lines.extend(
+            self.files
+                .iter()
+                .map(|(name, size)| format!("{} {}", size, name)),
+        );
+        for (name, dir) in &self.dirs {
+            lines.push(format!("$ cd {}", name));
+            dir.transcript_lines(lines);
+            lines.push("$ cd ..".to_string());
+        }
+    }
+
+    // The layout the puzzle uses to describe a filesystem.
+    pub fn tree(&self) -> String {
+        let mut lines = vec!["- / (dir)".to_string()];
+        self.tree_lines(1, &mut lines);
+        lines.join("\n")
+    }
+
+    fn tree_lines(&self, depth: usize, lines: &mut Vec<String>) {
+        let indent = "  ".repeat(depth);
+        let mut names = self
+            .dirs
+            .keys()
+            .chain(self.files.keys())
+            .collect::<Vec<_>>();
+        names.sort();
+        for name in names {
+            match (self.dirs.get(name), self.files.get(name)) {
+                (Some(dir), _) => {
+                    lines.push(format!("{}- {} (dir)", indent, name));
+                    dir.tree_lines(depth + 1, lines);
+                }
+                (None, Some(size)) => {
+                    lines.push(format!("{}- {} (file, size={})", indent, name, size))
                 }
+                (None, None) => unreachable!(),
+            }
+        }
+    }
+}
+
+// What `aoc22 fs` can show of a transcript's filesystem.
+#[derive(StructOpt)]
+pub enum Listing {
+    /// The total size of every directory.
+    Du,
+    /// Files and directories within a range of sizes.
+    Find {
+        #[structopt(long)]
+        min_size: Option<u64>,
+        #[structopt(long)]
+        max_size: Option<u64>,
+    },
+    /// Everything, laid out like the puzzle does.
+    Tree,
+    /// Makes the filesystem for real, with sparse files.
+    Materialize {
+        /// Where to make it - by default a new directory under the system's
+        /// temporary directory.
+        #[structopt(parse(from_os_str))]
+        into: Option<PathBuf>,
+    },
+    /// Prints a transcript exploring a real directory, instead of reading one.
+    Transcribe {
+        #[structopt(parse(from_os_str))]
+        dir: PathBuf,
+    },
+}
+
+// Puts the path an error is about in front of it.
+fn about(path: &Path) -> impl Fn(io::Error) -> io::Error + '_ {
+    move |e| io::Error::new(e.kind(), format!("{}: {}", path.display(), e))
+}
+
+pub fn explore(transcript: Option<&Path>, listing: &Listing) -> io::Result<()> {
+    if let Listing::Transcribe { dir } = listing {
+        println!("{}", Dir::read(dir).map_err(about(dir))?.transcript());
+        return Ok(());
+    }
+    let invalid = |e: Diagnostic| io::Error::new(io::ErrorKind::InvalidData, e.to_string());
+    let root = match transcript {
+        Some(path) => fs::read_to_string(path)
+            .and_then(|transcript| Dir::parse(&transcript).map_err(invalid))
+            .map_err(about(path))?,
+        None => Dir::parse(include_str!("../inputs/day07.txt")).map_err(invalid)?,
+    };
+    match listing {
+        Listing::Du => {
+            for (path, size) in root.du() {
+                println!("{}\t{}", size, path);
             }
-            lookup.insert(format!("/{}", current_dir.join("/")), (fs, local_size));
         }
+        Listing::Find { min_size, max_size } => {
+            for entry in root.find(*min_size, *max_size) {
+                let kind = if entry.is_dir { "dir" } else { "file" };
+                println!("{}\t{}\t{}", entry.size, kind, entry.path);
+            }
+        }
+        Listing::Tree => println!("{}", root.tree()),
+        Listing::Materialize { into } => {
+            let into = into.clone().unwrap_or_else(|| {
+                env::temp_dir().join(format!("aoc22-fs-{}", std::process::id()))
+            });
+            root.materialize(&into).map_err(about(&into))?;
+            println!("{}", into.display());
+        }
+        Listing::Transcribe { .. } => unreachable!(),
     }
-    let mut part_a = 0;
+    Ok(())
+}
+
+// The transcript's problems, if it has any.
+pub(crate) fn validate(input: &str) -> Vec<Diagnostic> {
+    Dir::parse(input).err().into_iter().collect()
+}
 
-    let mut directory_sizes = vec![];
-    for k in lookup.keys() {
-        let size = calculate_size(&lookup, k);
-        if size <= 100000 {
-            part_a += size;
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Goal {
+    // Free enough while deleting as little as possible.
+    FewestBytes,
+    // Free enough while deleting as few directories as possible.
+    FewestDirs,
+}
+
+impl Goal {
+    fn key(self, freed: u64, count: usize) -> (u64, u64) {
+        match self {
+            Goal::FewestBytes => (freed, count as u64),
+            Goal::FewestDirs => (count as u64, freed),
         }
-        directory_sizes.push((k, size));
     }
-    println!("Part A is: {}", part_a);
+}
+
+#[derive(Debug, PartialEq, Eq)]
+pub struct Plan {
+    pub dirs: Vec<String>,
+    pub freed: u64,
+}
 
-    let total_size = 70000000;
-    let required_space = 30000000;
-    let used_size = calculate_size(&lookup, &"/".to_string());
-    let max_size = total_size - required_space;
+// A directory as the search meets them, after the ones before it and their
+// contents.
+struct Node {
+    path: String,
+    size: u64,
+    // Where the directories after everything inside this one start.
+    end: usize,
+}
 
-    let min_directory_to_delete = used_size - max_size;
+fn flatten(dir: &Dir, path: &str, nodes: &mut Vec<Node>) {
+    for (name, child) in &dir.dirs {
+        let i = nodes.len();
+        let path = join(path, name);
+        nodes.push(Node {
+            path: path.clone(),
+            size: child.size(),
+            end: 0,
+        });
+        flatten(child, &path, nodes);
+        nodes[i].end = nodes.len();
+    }
+}
+
+// Branch and bound over which directories to delete. Keeping a table of every
+// amount that can be freed instead grows with `needed`, which is millions of
+// bytes apart even for generated transcripts of a few hundred directories.
+struct Search {
+    nodes: Vec<Node>,
+    needed: u64,
+    goal: Goal,
+    // most[k][i] is the most deleting k directories from the i-th on can free,
+    // filled in as far as it's needed.
+    most: Vec<Vec<u64>>,
+    // The most that deleting any number from the i-th on can free.
+    any: Vec<u64>,
+    // A segment tree over the directories, each span's (size, index) pairs
+    // sorted, to find the one smallest directory that's enough.
+    sorted: Vec<Vec<(u64, usize)>>,
+    picked: Vec<usize>,
+    // How many directories a plan can have in this round.
+    limit: usize,
+    best: Option<(u64, Vec<usize>)>,
+}
 
-    directory_sizes.sort_by(|(_, s1), (_, s2)| s1.cmp(s2));
-    println!(
-        "Part B is: {:?}",
-        directory_sizes
+impl Search {
+    fn new(root: &Dir, needed: u64, goal: Goal) -> Search {
+        let mut nodes = vec![];
+        flatten(root, "", &mut nodes);
+        let mut any = vec![0; nodes.len() + 1];
+        for (i, node) in nodes.iter().enumerate().rev() {
+            any[i] = any[i + 1].max(node.size + any[node.end]);
+        }
+        let n = nodes.len();
+        let mut sorted = vec![vec![]; 2 * n];
+        for (i, node) in nodes.iter().enumerate() {
+            sorted[n + i] = vec![(node.size, i)];
+        }
+        for i in (1..n).rev() {
+            let mut span = [&sorted[2 * i][..], &sorted[2 * i + 1][..]].concat();
+            span.sort_unstable();
+            sorted[i] = span;
+        }
+        Search {
+            most: vec![vec![0; nodes.len() + 1]],
+            nodes,
+            needed,
+            goal,
+            any,
+            sorted,
+            picked: vec![],
+            limit: 0,
+            best: None,
+        }
+    }
+
+    // Allows one more directory each round, so plans with few directories are
+    // found - or ruled out - cheaply before looking at bigger ones.
+    fn run(mut self) -> Option<(u64, Vec<usize>)> {
+        if self.needed == 0 {
+            return Some((0, vec![]));
+        }
+        while self.limit < self.nodes.len() {
+            self.limit += 1;
+            self.search(0, 0);
+            match (&self.best, self.goal) {
+                (Some(_), Goal::FewestDirs) => break,
+                (Some((freed, _)), Goal::FewestBytes) if *freed == self.needed => break,
+                _ => {}
+            }
+        }
+        self.best
+    }
+
+    fn most(&mut self, k: usize, i: usize) -> u64 {
+        if k >= self.nodes.len() {
+            return self.any[i];
+        }
+        while self.most.len() <= k {
+            let fewer = self.most.last().unwrap();
+            let mut row = vec![0; self.nodes.len() + 1];
+            for (i, node) in self.nodes.iter().enumerate().rev() {
+                row[i] = row[i + 1].max(node.size + fewer[node.end]);
+            }
+            self.most.push(row);
+        }
+        self.most[k][i]
+    }
+
+    // The smallest directory from the i-th on that frees at least `size`.
+    fn smallest(&self, i: usize, size: u64) -> Option<(u64, usize)> {
+        let n = self.nodes.len();
+        let (mut l, mut r) = (i + n, 2 * n);
+        let mut smallest = None;
+        let mut check = |span: &[(u64, usize)]| {
+            let j = span.partition_point(|&(s, _)| s < size);
+            if let Some(&found) = span.get(j) {
+                if smallest.is_none_or(|s| found < s) {
+                    smallest = Some(found);
+                }
+            }
+        };
+        while l < r {
+            if l & 1 == 1 {
+                check(&self.sorted[l]);
+                l += 1;
+            }
+            if r & 1 == 1 {
+                r -= 1;
+                check(&self.sorted[r]);
+            }
+            l /= 2;
+            r /= 2;
+        }
+        smallest
+    }
+
+    fn beats_best(&self, freed: u64, count: usize) -> bool {
+        self.best.as_ref().is_none_or(|(best, picked)| {
+            self.goal.key(freed, count) < self.goal.key(*best, picked.len())
+        })
+    }
+
+    // Looks for plans from the i-th directory on, on top of the ones picked.
+    fn search(&mut self, i: usize, freed: u64) {
+        let count = self.picked.len();
+        // Whatever's found from here frees at least `needed` with at least
+        // one more directory.
+        if !self.beats_best(self.needed, count + 1) {
+            return;
+        }
+        let allowed = match (&self.best, self.goal) {
+            (Some((_, picked)), Goal::FewestDirs) => picked.len() - count,
+            (Some((best, picked)), Goal::FewestBytes) if *best == self.needed => {
+                picked.len() - count - 1
+            }
+            _ => usize::MAX,
+        }
+        .min(self.limit - count);
+        if freed + self.most(allowed, i) < self.needed {
+            return;
+        }
+        if allowed == 1 {
+            // Nothing after the last directory matters but its size.
+            if let Some((size, j)) = self.smallest(i, self.needed - freed) {
+                if self.beats_best(freed + size, count + 1) {
+                    let mut picked = self.picked.clone();
+                    picked.push(j);
+                    self.best = Some((freed + size, picked));
+                }
+            }
+            return;
+        }
+
+        // Delete the i-th directory whole...
+        let (size, end) = (self.nodes[i].size, self.nodes[i].end);
+        self.picked.push(i);
+        if freed + size < self.needed {
+            self.search(end, freed + size);
+        } else if self.beats_best(freed + size, count + 1) {
+            self.best = Some((freed + size, self.picked.clone()));
+        }
+        self.picked.pop();
+        // ...or keep it and look inside.
+        self.search(i + 1, freed);
+    }
+}
+
+impl Dir {
+    // The best set of directories, none inside another, to delete to free at
+    // least `needed` bytes. The root can't be deleted.
+    pub fn plan(&self, needed: u64, goal: Goal) -> Option<Plan> {
+        let search = Search::new(self, needed, goal);
+        let paths = search
+            .nodes
             .iter()
-            .find(|(_, s)| s >= &min_directory_to_delete)
-            .unwrap()
-            .1
-    );
+            .map(|n| n.path.clone())
+            .collect::<Vec<_>>();
+        let (freed, picked) = search.run()?;
+        Some(Plan {
+            dirs: picked.into_iter().map(|i| paths[i].clone()).collect(),
+            freed,
+        })
+    }
 }
 
-pub fn day07() {
-    let input = include_str!("../inputs/day07.txt");
-    let commands = parse_input(input);
-    solve(commands);
+// How much has to be deleted to leave `required_space` free on a disk of
+// `disk_size`.
+fn space_needed(root: &Dir, disk_size: u64, required_space: u64) -> u64 {
+    (root.size() + required_space).saturating_sub(disk_size)
 }
 
-#[test]
-fn sample_input_7() {
-    let input = r#"$ cd /
+fn solve(root: &Dir, needed: u64) -> (u64, Option<u64>) {
+    let sizes = root.du();
+    let part_a = sizes
+        .iter()
+        .map(|&(_, size)| size)
+        .filter(|&size| size <= 100000)
+        .sum();
+
+    let part_b = sizes
+        .iter()
+        .map(|&(_, size)| size)
+        .filter(|&size| size >= needed)
+        .min();
+    (part_a, part_b)
+}
+
+pub fn day07(ctx: &Context) {
+    let input = ctx.input(include_str!("../inputs/day07.txt"));
+    let root = Dir::parse(input).unwrap_or_else(|e| panic!("Bad transcript: {}", e));
+    let needed = space_needed(
+        &root,
+        ctx.config("disk_size", 70000000),
+        ctx.config("required_space", 30000000),
+    );
+    let (part_a, part_b) = solve(&root, needed);
+    ctx.answer(Part::A, part_a);
+    match part_b {
+        Some(part_b) => ctx.answer(Part::B, part_b),
+        None => ctx.diagnostic(format!("No directory frees {} bytes", needed)),
+    }
+
+    let goal = match ctx.config("plan", String::new()).as_str() {
+        "" => return,
+        "bytes" => Goal::FewestBytes,
+        "dirs" => Goal::FewestDirs,
+        other => panic!("Bad value for plan: {}", other),
+    };
+    match root.plan(needed, goal) {
+        Some(plan) => {
+            for dir in &plan.dirs {
+                ctx.diagnostic(format!("Delete {}", dir));
+            }
+            ctx.diagnostic(format!(
+                "Frees {} bytes of the {} needed",
+                plan.freed, needed
+            ));
+        }
+        None => ctx.diagnostic(format!("Nothing short of / frees {} bytes", needed)),
+    }
+}
+
+#[cfg(test)]
+const SAMPLE: &str = r#"$ cd /
 $ ls
 dir a
 14848514 b.txt
@@ -117,7 +700,170 @@ $ ls
 5626152 d.ext
 7214296 k"#;
 
-    let commands = parse_input(input);
-    solve(commands);
-    assert!(false);
+#[test]
+fn sample_input_7() {
+    let root = Dir::parse(SAMPLE).unwrap();
+    let needed = space_needed(&root, 70000000, 30000000);
+    assert_eq!((95437, Some(24933642)), solve(&root, needed));
+
+    let du = root.du();
+    assert_eq!(
+        vec![
+            ("/a/e".to_string(), 584),
+            ("/a".to_string(), 94853),
+            ("/d".to_string(), 24933642),
+            ("/".to_string(), 48381165),
+        ],
+        du
+    );
+
+    let found = root
+        .find(Some(60000), Some(8100000))
+        .into_iter()
+        .map(|e| (e.path, e.is_dir))
+        .collect::<Vec<_>>();
+    let expected = [
+        ("/a/h.lst", false),
+        ("/a", true),
+        ("/d/d.ext", false),
+        ("/d/d.log", false),
+        ("/d/j", false),
+        ("/d/k", false),
+    ];
+    assert_eq!(
+        expected
+            .iter()
+            .map(|&(p, d)| (p.to_string(), d))
+            .collect::<Vec<_>>(),
+        found
+    );
+
+    let tree = root.tree();
+    assert!(tree.starts_with("- / (dir)\n  - a (dir)\n    - e (dir)\n      - i (file, size=584)\n    - f (file, size=29116)"));
+    assert!(tree.ends_with("    - k (file, size=7214296)"));
+}
+
+// The sample made for real and read back, sizes and all.
+#[test]
+fn real_directory_round_trip() {
+    let root = Dir::parse(SAMPLE).unwrap();
+    assert_eq!(Ok(&root), Dir::parse(&root.transcript()).as_ref());
+
+    let path = env::temp_dir().join("aoc22-day07-round-trip");
+    if path.exists() {
+        fs::remove_dir_all(&path).unwrap();
+    }
+    root.materialize(&path).unwrap();
+    let read = Dir::read(&path).unwrap();
+    fs::remove_dir_all(&path).unwrap();
+    assert_eq!(root, read);
+    assert_eq!(root.du(), read.du());
+}
+
+// Plans checked against trying every set of directories in small random trees.
+#[test]
+fn plan_deletions() {
+    use std::collections::HashMap;
+
+    let root = Dir::parse(SAMPLE).unwrap();
+    let plan = root.plan(94000, Goal::FewestBytes).unwrap();
+    assert_eq!((vec!["/a".to_string()], 94853), (plan.dirs, plan.freed));
+    assert_eq!(None, root.plan(48381165, Goal::FewestDirs));
+
+    // Each directory after the root hangs off an earlier one.
+    type Tree = Vec<(usize, u64)>;
+    fn build(tree: &Tree) -> (Dir, Vec<String>) {
+        let mut paths = vec![vec![]];
+        let mut root = Dir::default();
+        for (i, &(parent, size)) in tree.iter().enumerate() {
+            let mut path = paths[parent].clone();
+            path.push(format!("d{}", i));
+            let dir = root.dir_mut(&path);
+            dir.files.insert("f".to_string(), size);
+            paths.push(path);
+        }
+        let paths = paths.iter().map(|p| format!("/{}", p.join("/"))).collect();
+        (root, paths)
+    }
+
+    crate::differential::check(
+        300,
+        |rng| {
+            let tree = (0..rng.below(8))
+                .map(|i| (rng.below(i + 1), rng.range(0, 9) as u64))
+                .collect::<Tree>();
+            let goal = if rng.chance(50) {
+                Goal::FewestBytes
+            } else {
+                Goal::FewestDirs
+            };
+            (tree, rng.range(0, 40) as u64, goal)
+        },
+        |_| vec![],
+        |(tree, needed, goal)| {
+            let (root, paths) = build(tree);
+            let sizes = root.du().into_iter().collect::<HashMap<_, _>>();
+            let dirs = &paths[1..];
+            let best = (0..1u32 << dirs.len())
+                .map(|set| {
+                    (0..dirs.len())
+                        .filter(|i| set & 1 << i != 0)
+                        .map(|i| &dirs[i])
+                        .collect::<Vec<_>>()
+                })
+                .filter(|set| {
+                    set.iter()
+                        .all(|a| set.iter().all(|b| !b.starts_with(&format!("{}/", a))))
+                })
+                .map(|set| (set.iter().map(|d| sizes[*d]).sum::<u64>(), set.len()))
+                .filter(|&(freed, _)| freed >= *needed)
+                .min_by_key(|&(freed, count)| goal.key(freed, count));
+            best
+        },
+        |(tree, needed, goal)| {
+            let (root, _) = build(tree);
+            let sizes = root.du().into_iter().collect::<HashMap<_, _>>();
+            let check = |plan: Plan| {
+                assert_eq!(plan.freed, plan.dirs.iter().map(|d| sizes[d]).sum::<u64>());
+                (plan.freed, plan.dirs.len())
+            };
+            root.plan(*needed, *goal).map(check)
+        },
+    );
+
+    // Far more directories than the random trees, with sizes in the millions.
+    let root = Dir::parse(&crate::generate::generate(7, 5, 300).unwrap()).unwrap();
+    let needed = space_needed(&root, 70000000, 30000000);
+    let plan = root.plan(needed, Goal::FewestBytes).unwrap();
+    assert_eq!(needed, plan.freed);
+}
+
+#[test]
+fn bad_transcripts() {
+    let error = |transcript: &str| Dir::parse(transcript).unwrap_err().to_string();
+    assert_eq!(
+        "line 6: /a doesn't match how it was listed on line 4",
+        error("$ ls\ndir a\n$ cd a\n$ ls\n1 b\n$ ls\n2 b")
+    );
+    assert_eq!(
+        "line 2: unknown command \"rm -rf a\"",
+        error("$ ls\n$ rm -rf a")
+    );
+    assert_eq!(
+        "line 3: cd .. from the root",
+        error("$ cd /\n$ ls\n$ cd ..")
+    );
+    assert_eq!(
+        "line 4: b hasn't been listed in /a",
+        error("$ ls\ndir a\n$ cd a\n$ cd b")
+    );
+    assert_eq!("line 1: output without an ls", error("dir a\n$ ls"));
+    assert_eq!("line 3: a is listed twice", error("$ ls\ndir a\n10 a"));
+    assert_eq!("line 2: \"x\" is not a size", error("$ ls\nx a"));
+    assert_eq!("line 2: \"..\" can't be a name", error("$ ls\ndir .."));
+
+    // Listing a directory again the same way is fine.
+    let mut relisted = SAMPLE.to_string();
+    relisted.push_str("\n$ cd /\n$ ls\ndir d\ndir a\n8504156 c.dat\n14848514 b.txt");
+    assert_eq!(Dir::parse(SAMPLE), Dir::parse(&relisted));
 }
@@ -1,3 +1,8 @@
+use crate::{
+    context::{Context, Part},
+    validate::Diagnostic,
+};
+
 #[derive(Debug, Clone)]
 struct Tree {
     i: usize,
@@ -76,6 +81,48 @@ fn how_scenic(tree_grid: Vec<Vec<Tree>>) -> Vec<Vec<u32>> {
     score_grid
 }
 
+// Walks outwards from every tree in each direction, returning how many trees
+// are visible and the best scenic score.
+#[cfg(test)]
+fn naive_visible_and_scenic(input: &str) -> (i32, u32) {
+    let grid = input
+        .lines()
+        .map(|l| {
+            l.chars()
+                .map(|c| c.to_digit(10).unwrap())
+                .collect::<Vec<_>>()
+        })
+        .collect::<Vec<_>>();
+    let mut visible = 0;
+    let mut best = 0;
+    for i in 0..grid.len() {
+        for j in 0..grid[i].len() {
+            let mut seen_from_outside = false;
+            let mut score = 1;
+            for (di, dj) in [(-1, 0), (1, 0), (0, -1), (0, 1)] {
+                let (mut x, mut y) = (i as i32 + di, j as i32 + dj);
+                let mut distance = 0;
+                let mut blocked = false;
+                while x >= 0 && y >= 0 && (x as usize) < grid.len() && (y as usize) < grid[i].len()
+                {
+                    distance += 1;
+                    if grid[x as usize][y as usize] >= grid[i][j] {
+                        blocked = true;
+                        break;
+                    }
+                    x += di;
+                    y += dj;
+                }
+                seen_from_outside |= !blocked;
+                score *= distance;
+            }
+            visible += seen_from_outside as i32;
+            best = best.max(score);
+        }
+    }
+    (visible, best)
+}
+
 fn build(input: &str) -> Vec<Vec<Tree>> {
     let mut tree_grid: Vec<Vec<Tree>> = vec![];
     for (i, line) in input.lines().enumerate() {
@@ -158,16 +205,41 @@ fn build(input: &str) -> Vec<Vec<Tree>> {
     tree_grid
 }
 
-pub fn day08() {
-    let input = include_str!("../inputs/day08.txt");
+// `build` reads each tree as a single digit and expects a rectangular grid.
+pub(crate) fn validate(input: &str) -> Vec<Diagnostic> {
+    let mut diagnostics = vec![];
+    let width = input.lines().next().map_or(0, |l| l.len());
+    if width == 0 {
+        diagnostics.push(Diagnostic::new("no trees"));
+    }
+    for (i, line) in input.lines().enumerate() {
+        if line.len() != width {
+            diagnostics.push(Diagnostic::at(
+                i,
+                format!("{} trees, but the first row has {}", line.len(), width),
+            ));
+        }
+        if let Some(c) = line.chars().find(|c| !c.is_ascii_digit()) {
+            diagnostics.push(Diagnostic::at(
+                i,
+                format!("{:?} is not a height from 0 to 9", c),
+            ));
+        }
+    }
+    diagnostics
+}
+
+pub fn day08(ctx: &Context) {
+    let input = ctx.input(include_str!("../inputs/day08.txt"));
 
     let grid = build(input);
 
-    println!("Part A is {}", how_visible(grid.clone()));
-    println!(
-        "Part B is {}",
-        how_scenic(grid).iter().flatten().max().unwrap()
-    );
+    if ctx.wants(Part::A) {
+        ctx.answer(Part::A, how_visible(grid.clone()));
+    }
+    if ctx.wants(Part::B) {
+        ctx.answer(Part::B, how_scenic(grid).iter().flatten().max().unwrap());
+    }
 }
 
 #[test]
@@ -239,3 +311,21 @@ fn real_input() {
             .unwrap()
     );
 }
+
+#[test]
+fn nearest_heights_match_naive() {
+    crate::differential::check(
+        200,
+        |rng| crate::generate::generate(8, rng.next_u64(), rng.range(1, 8) as usize).unwrap(),
+        |input: &String| {
+            let mut smaller = crate::differential::without_each_line(input, 1);
+            smaller.extend(crate::differential::without_each_column(input, 1));
+            smaller
+        },
+        |input| naive_visible_and_scenic(input),
+        |input| {
+            let best = *how_scenic(build(input)).iter().flatten().max().unwrap();
+            (how_visible(build(input)), best)
+        },
+    );
+}
@@ -2,6 +2,8 @@ use std::{collections::HashSet, hash::Hash};
 
 use regex::Regex;
 
+use crate::context::{Context, Part};
+
 #[derive(Eq, PartialEq, Hash, Clone)]
 struct Coords {
     x: i32,
@@ -77,12 +79,16 @@ fn solve_with_knots(input: &str, knot_count: u8) -> HashSet<Coords> {
     tail_visits
 }
 
-pub fn day09() {
-    let input = include_str!("../inputs/day09.txt");
+pub fn day09(ctx: &Context) {
+    let input = ctx.input(include_str!("../inputs/day09.txt"));
 
-    let tail_visits = solve_with_knots(input, 2);
-    println!("Part A is: {}", tail_visits.len());
+    if ctx.wants(Part::A) {
+        let tail_visits = solve_with_knots(input, 2);
+        ctx.answer(Part::A, tail_visits.len());
+    }
 
-    let tail_visits = solve_with_knots(input, 10);
-    println!("Part B is: {}", tail_visits.len());
+    if ctx.wants(Part::B) {
+        let tail_visits = solve_with_knots(input, 10);
+        ctx.answer(Part::B, tail_visits.len());
+    }
 }
@@ -1,14 +1,16 @@
 use regex::Regex;
 
-// Prints pixel - also handles newlines for us.
-fn print_pixel(x: i32, cycle: i32) {
+use crate::context::{Context, Part};
+
+// Draws pixel - also handles newlines for us.
+fn draw_pixel(screen: &mut String, x: i32, cycle: i32) {
     if i32::abs(x - (cycle - 1) % 40) <= 1 {
-        print!("#");
+        screen.push('#');
     } else {
-        print!(".");
+        screen.push('.');
     }
     if cycle % 40 == 0 {
-        println!();
+        screen.push('\n');
     }
 }
 
@@ -18,43 +20,44 @@ fn check_and_update_sum(x: i32, cycle: i32, sum: &mut i32) {
     }
 }
 
-fn run_program(s: &str) -> i32 {
+// Returns the signal strength sum and what the CRT shows.
+fn run_program(s: &str) -> (i32, String) {
     let re = Regex::new(r"addx (.*)").unwrap();
 
     let mut cycle = 0;
     let mut x = 1;
     let mut sum = 0;
+    let mut screen = String::new();
     // Whenever the cycle is incremented, we must:
     // - CRT renders pixel
     // - Check for the sum
     for instruction in s.lines() {
         cycle += 1;
-        print_pixel(x, cycle);
+        draw_pixel(&mut screen, x, cycle);
         check_and_update_sum(x, cycle, &mut sum);
 
         if instruction != "noop" {
             cycle += 1;
-            print_pixel(x, cycle);
+            draw_pixel(&mut screen, x, cycle);
             check_and_update_sum(x, cycle, &mut sum);
 
             x += re.captures(instruction).unwrap()[1].parse::<i32>().unwrap();
         }
     }
-    sum
+    (sum, screen.trim_end().to_string())
 }
 
-pub fn day10() {
-    let input = include_str!("../inputs/day10.txt");
-    let sum = run_program(input);
-    println!("Part A is: {}", sum);
+pub fn day10(ctx: &Context) {
+    let input = ctx.input(include_str!("../inputs/day10.txt"));
+    let (sum, screen) = run_program(input);
+    ctx.answer(Part::A, sum);
+    ctx.answer(Part::B, screen);
 }
 
 #[test]
 fn sample_input() {
-    assert_eq!(
-        13140,
-        run_program(
-            "addx 15
+    let (sum, screen) = run_program(
+        "addx 15
 addx -11
 addx 6
 addx -3
@@ -199,7 +202,16 @@ addx -6
 addx -11
 noop
 noop
-noop"
-        )
-    )
+noop",
+    );
+    assert_eq!(13140, sum);
+    assert_eq!(
+        "##..##..##..##..##..##..##..##..##..##..
+###...###...###...###...###...###...###.
+####....####....####....####....####....
+#####.....#####.....#####.....#####.....
+######......######......######......####
+#######.......#######.......#######.....",
+        screen
+    );
 }
@@ -1,3 +1,5 @@
+use crate::context::{Context, Part};
+
 #[derive(Debug, Clone)]
 struct Monkey {
     items: Vec<u64>,
@@ -104,7 +106,7 @@ fn hand_fed_input() -> Vec<Monkey> {
     ]
 }
 
-fn solve(monkeys: &mut Vec<Monkey>, rounds: u64, part_b: bool) -> u64 {
+fn solve(ctx: &Context, monkeys: &mut [Monkey], rounds: u64, part_b: bool) -> u64 {
     let modulus = monkeys.iter().map(|m| m.check).product::<u32>();
     for _ in 1..=rounds {
         for i in 0..monkeys.len() {
@@ -121,18 +123,22 @@ fn solve(monkeys: &mut Vec<Monkey>, rounds: u64, part_b: bool) -> u64 {
         .iter()
         .map(|elem| elem.total_items_inspected)
         .collect::<Vec<u64>>();
-    println!("Each monkey inspected: {:?}", inspected);
+    ctx.diagnostic(format!("Each monkey inspected: {:?}", inspected));
     inspected.sort();
     inspected.reverse();
     inspected.iter().take(2).product::<u64>()
 }
 
-pub fn day11() {
-    let _input = include_str!("../inputs/day11.txt");
+pub fn day11(ctx: &Context) {
+    let _input = ctx.input(include_str!("../inputs/day11.txt"));
     let monkeys = hand_fed_input();
 
-    println!("Part A is: {}", solve(&mut monkeys.clone(), 20, false));
-    println!("Part B is: {:?}", solve(&mut monkeys.clone(), 10000, true));
+    if ctx.wants(Part::A) {
+        ctx.answer(Part::A, solve(ctx, &mut monkeys.clone(), 20, false));
+    }
+    if ctx.wants(Part::B) {
+        ctx.answer(Part::B, solve(ctx, &mut monkeys.clone(), 10000, true));
+    }
 }
 
 #[test]
@@ -168,6 +174,11 @@ fn sample_input() {
         },
     ];
 
-    assert_eq!(10605, solve(&mut monkeys.clone(), 20, false));
-    assert_eq!(2713310158, solve(&mut monkeys.clone(), 10000, true));
+    let (ctx, _, diagnostics) = Context::captured();
+    assert_eq!(10605, solve(&ctx, &mut monkeys.clone(), 20, false));
+    assert_eq!(
+        "Each monkey inspected: [101, 95, 7, 105]\n",
+        diagnostics.contents()
+    );
+    assert_eq!(2713310158, solve(&ctx, &mut monkeys.clone(), 10000, true));
 }
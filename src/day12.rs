@@ -2,6 +2,8 @@ use std::collections::{HashSet, VecDeque};
 
 use itertools::Itertools;
 
+use crate::context::{Context, Part};
+
 #[derive(Debug, Clone, Hash)]
 struct Coord {
     x: usize,
@@ -121,16 +123,20 @@ fn parse_input(input: &str) -> (Vec<Vec<Coord>>, Coord, Coord) {
     (grid, start.unwrap(), end.unwrap())
 }
 
-pub fn day12() {
-    let input = include_str!("../inputs/day12.txt");
+pub fn day12(ctx: &Context) {
+    let input = ctx.input(include_str!("../inputs/day12.txt"));
 
     let (grid, start, end) = parse_input(input);
 
-    let path = bfs(&grid, &start, &end, true);
-    println!("Part A is: {:?}", path.len() - 1);
+    if ctx.wants(Part::A) {
+        let path = bfs(&grid, &start, &end, true);
+        ctx.answer(Part::A, path.len() - 1);
+    }
 
-    let path = bfs(&grid, &end, &start, false);
-    println!("Part B is: {:?}", path.len() - 1);
+    if ctx.wants(Part::B) {
+        let path = bfs(&grid, &end, &start, false);
+        ctx.answer(Part::B, path.len() - 1);
+    }
 }
 
 // #[test]
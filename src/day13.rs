@@ -1,6 +1,8 @@
 use std::fmt::Debug;
 use std::vec;
 
+use crate::context::{Context, Part};
+
 #[derive(Eq, Clone)]
 enum List {
     Val(u32),
@@ -12,10 +14,7 @@ impl PartialEq for List {
         match self {
             List::Val(v1) => match other {
                 List::Val(v2) => v1 == v2,
-                List::Ls(vec2) => {
-                    println!(" {:?} with {}", vec2, v1);
-                    vec2.eq(&vec![List::Val(v1.clone())])
-                }
+                List::Ls(vec2) => vec2.eq(&vec![List::Val(*v1)]),
             },
             List::Ls(vec1) => match other {
                 List::Val(v2) => vec1.eq(&vec![List::Val(v2.clone())]),
@@ -148,11 +147,15 @@ fn part_a(input: &str) -> u32 {
     sum
 }
 
-pub fn day13() {
-    let input = include_str!("../inputs/day13.txt");
+pub fn day13(ctx: &Context) {
+    let input = ctx.input(include_str!("../inputs/day13.txt"));
 
-    let sum = part_a(input.clone());
-    println!("Part A is {}", sum);
+    if ctx.wants(Part::A) {
+        ctx.answer(Part::A, part_a(input));
+    }
+    if !ctx.wants(Part::B) {
+        return;
+    }
 
     let mut all_messages = input
         .split("\n")
@@ -165,10 +168,10 @@ pub fn day13() {
     all_messages.push(divider_6.clone());
     all_messages.sort();
 
-    println!(
-        "Part B is {}",
+    ctx.answer(
+        Part::B,
         (all_messages.binary_search(&divider_2).unwrap() + 1)
-            * (all_messages.binary_search(&divider_6).unwrap() + 1)
+            * (all_messages.binary_search(&divider_6).unwrap() + 1),
     );
 }
 
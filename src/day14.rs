@@ -2,6 +2,8 @@ use std::collections::HashSet;
 
 use itertools::Itertools;
 
+use crate::context::{Context, Part};
+
 #[derive(PartialEq, Eq, Hash, Debug)]
 struct Coord {
     x: usize,
@@ -52,11 +54,11 @@ fn parse_input(input: &str) -> HashSet<Coord> {
 }
 
 // Returns whether it came to rest or not
-fn drop_sand(coords: &mut HashSet<Coord>, max_y: usize) -> bool {
+fn drop_sand(ctx: &Context, coords: &mut HashSet<Coord>, max_y: usize) -> bool {
     let mut pos = Coord { x: 500, y: 0 };
     loop {
         if pos.y > max_y {
-            println!("Returning full because bigger than {}", max_y);
+            ctx.diagnostic(format!("Returning full because bigger than {}", max_y));
             return false;
         }
         if !coords.contains(&Coord {
@@ -79,7 +81,7 @@ fn drop_sand(coords: &mut HashSet<Coord>, max_y: usize) -> bool {
         } else {
             // If we've not been able to move anywhere, we're full.
             if pos == (Coord { x: 500, y: 0 }) {
-                println!("Returning because full");
+                ctx.diagnostic("Returning because full");
                 return false;
             }
             coords.insert(pos);
@@ -88,16 +90,21 @@ fn drop_sand(coords: &mut HashSet<Coord>, max_y: usize) -> bool {
     }
 }
 
-pub fn day14() {
-    let input = include_str!("../inputs/day14.txt");
+pub fn day14(ctx: &Context) {
+    let input = ctx.input(include_str!("../inputs/day14.txt"));
     let mut coords = parse_input(input);
     let max_y = coords.iter().map(|c| c.y).max().unwrap();
 
-    let mut count = 0;
-    while drop_sand(&mut coords, max_y) {
-        count += 1;
+    if ctx.wants(Part::A) {
+        let mut count = 0;
+        while drop_sand(ctx, &mut coords, max_y) {
+            count += 1;
+        }
+        ctx.answer(Part::A, count);
+    }
+    if !ctx.wants(Part::B) {
+        return;
     }
-    println!("Part A is: {}", count);
 
     let mut coords = parse_input(input);
     let min_x = coords.iter().map(|c| c.x).min().unwrap();
@@ -107,22 +114,23 @@ pub fn day14() {
     }
 
     let mut count = 0;
-    while drop_sand(&mut coords, max_y + 2) {
+    while drop_sand(ctx, &mut coords, max_y + 2) {
         count += 1;
     }
-    println!("Part B is: {}", count + 1);
+    ctx.answer(Part::B, count + 1);
 }
 
 #[test]
 fn sample_input() {
     let input = r#"498,4 -> 498,6 -> 496,6
 503,4 -> 502,4 -> 502,9 -> 494,9"#;
+    let ctx = &Context::default();
 
     let mut coords = parse_input(input);
     let max_y = coords.iter().map(|c| c.y).max().unwrap();
 
     let mut count = 0;
-    while drop_sand(&mut coords, max_y) {
+    while drop_sand(ctx, &mut coords, max_y) {
         count += 1;
     }
 
@@ -136,7 +144,7 @@ fn sample_input() {
     }
 
     let mut count = 0;
-    while drop_sand(&mut coords, max_y + 2) {
+    while drop_sand(ctx, &mut coords, max_y + 2) {
         count += 1;
     }
     assert_eq!(count + 1, 93);
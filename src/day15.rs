@@ -1,7 +1,12 @@
-use std::collections::HashMap;
+use std::collections::{HashMap, HashSet};
 
 use regex::Regex;
 
+use crate::{
+    context::{Context, Part},
+    interval::IntervalSet,
+};
+
 #[derive(Debug, Eq, PartialEq, Hash, PartialOrd, Ord)]
 struct Coord {
     x: i32,
@@ -36,83 +41,84 @@ fn parse_input(input: &str) -> HashMap<Coord, Coord> {
     hm
 }
 
-// Finds the number of impossible beacons at a given y co-ordinate.
-fn find_impossible_beacons(
+// Every x position at a given y co-ordinate that is within range of a sensor.
+fn covered(hm: &HashMap<Coord, Coord>, target_y: i32) -> IntervalSet {
+    hm.iter()
+        .map(|(s, b)| {
+            // Negative when the sensor can't reach this row, leaving an empty range.
+            let diff = s.distance(b) - (s.y - target_y).abs();
+            i64::from(s.x - diff)..=i64::from(s.x + diff)
+        })
+        .collect()
+}
+
+fn bounds(lower_bound: Option<i32>, upper_bound: Option<i32>) -> IntervalSet {
+    let lb = lower_bound.unwrap_or(i32::MIN);
+    let ub = upper_bound.unwrap_or(i32::MAX);
+    IntervalSet::from(i64::from(lb)..=i64::from(ub))
+}
+
+// Finds the number of positions at a given y co-ordinate that are within range
+// of a sensor, optionally clamped to the given bounds.
+fn covered_positions(
     hm: &HashMap<Coord, Coord>,
     target_y: i32,
     lower_bound: Option<i32>,
     upper_bound: Option<i32>,
 ) -> i32 {
-    let mut v = vec![];
-    for (s, b) in hm {
-        let distance_to_y = (s.y - target_y).abs();
-        let diff = s.distance(b) - distance_to_y;
-        if diff >= 0 {
-            let lb = std::cmp::max(lower_bound.unwrap_or(i32::MIN), s.x - diff);
-            let ub = std::cmp::min(upper_bound.unwrap_or(i32::MAX), s.x + diff);
-            v.push((lb, true));
-            v.push((ub, false));
-        }
-    }
+    let bounds = bounds(lower_bound, upper_bound);
+    covered(hm, target_y).intersection(&bounds).len() as i32
+}
 
-    v.sort();
-    // Start with the first range.
-    let mut sum = 0;
-    let mut in_ranges: i32 = 0;
-    let mut previously_added = v[0].0;
-    let mut previous_bound = v[0].0;
-
-    for (b, entering_range) in v {
-        if in_ranges > 0 {
-            sum += b - previous_bound;
-
-            // Don't ask.
-            if previously_added < previous_bound {
-                sum += 1;
-            }
-            previously_added = b;
-        }
-        previous_bound = b;
-        if entering_range {
-            in_ranges += 1;
-        } else {
-            in_ranges -= 1;
-        }
-    }
-    assert!(in_ranges == 0);
-    sum
+// Finds the number of impossible beacons at a given y co-ordinate - every
+// covered position, apart from the beacons we already know about.
+fn find_impossible_beacons(
+    hm: &HashMap<Coord, Coord>,
+    target_y: i32,
+    lower_bound: Option<i32>,
+    upper_bound: Option<i32>,
+) -> i32 {
+    let bounds = bounds(lower_bound, upper_bound);
+    let known_beacons = hm
+        .values()
+        .filter(|b| b.y == target_y && bounds.contains(i64::from(b.x)))
+        .collect::<HashSet<_>>();
+    covered_positions(hm, target_y, lower_bound, upper_bound) - known_beacons.len() as i32
 }
 
-// Could there be a beacon at these coordinates?
-fn possible_beacon(hm: &HashMap<Coord, Coord>, c: Coord) -> bool {
-    for (s, b) in hm {
-        if s.distance(&c) <= s.distance(b) {
-            return false;
-        }
-    }
-    return true;
+// Checks every position in the row individually.
+#[cfg(test)]
+fn naive_impossible_beacons(hm: &HashMap<Coord, Coord>, target_y: i32, lb: i32, ub: i32) -> i32 {
+    (lb..=ub)
+        .filter(|&x| {
+            let c = Coord { x, y: target_y };
+            hm.iter().any(|(s, b)| s.distance(&c) <= s.distance(b)) && !hm.values().any(|b| b == &c)
+        })
+        .count() as i32
 }
 
-pub fn day15() {
-    let input = include_str!("../inputs/day15.txt");
+pub fn day15(ctx: &Context) {
+    let input = ctx.input(include_str!("../inputs/day15.txt"));
     let hm = parse_input(input);
 
-    let target_y = 2000000;
+    let target_y = ctx.config("target_y", 2000000);
+    let search_max = ctx.config("search_max", 4000000);
 
-    println!(
-        "Part A is: {}",
-        find_impossible_beacons(&hm, target_y, None, None)
-    );
+    if ctx.wants(Part::A) {
+        ctx.answer(Part::A, find_impossible_beacons(&hm, target_y, None, None));
+    }
+    if !ctx.wants(Part::B) {
+        return;
+    }
 
-    for y in 0..4000000 {
-        let impossible = find_impossible_beacons(&hm, y, Some(0), Some(4000000));
-        if impossible != 4000000 {
-            for x in 0..4000000 {
-                if possible_beacon(&hm, Coord { x, y }) {
-                    println!("Part B is: {}", x as i64 * 4000000 + y as i64);
-                    return;
-                }
-            }
+    // With everything outside the search area filled in, the distress beacon
+    // is the only gap left in any row.
+    let outside = IntervalSet::from(i64::MIN..=-1)
+        .union(&IntervalSet::from(i64::from(search_max) + 1..=i64::MAX));
+    for y in 0..=search_max {
+        if let Some(gap) = covered(&hm, y).union(&outside).gaps().next() {
+            ctx.answer(Part::B, gap.start() * 4000000 + i64::from(y));
+            return;
         }
     }
     //     println!("Part B is: {}", b);
@@ -145,3 +151,39 @@ pub fn day15() {
 //     }
 //     assert!(false);
 // }
+
+#[test]
+fn impossible_beacons_match_naive() {
+    crate::differential::check(
+        100,
+        |rng| {
+            let input = crate::generate::generate(15, rng.next_u64(), rng.range(1, 6) as usize);
+            (input.unwrap(), rng.range(0, 6000) as i32)
+        },
+        |(input, target_y)| {
+            crate::differential::without_each_line(input, 1)
+                .into_iter()
+                .map(|fewer| (fewer, *target_y))
+                .collect()
+        },
+        |(input, target_y)| {
+            let hm = parse_input(input);
+            let span = hm.keys().chain(hm.values()).map(|c| c.x).max().unwrap();
+            // Wide enough to hold every sensor's full reach.
+            let lb = hm.iter().map(|(s, b)| s.x - s.distance(b)).min().unwrap();
+            let ub = hm.iter().map(|(s, b)| s.x + s.distance(b)).max().unwrap();
+            (
+                naive_impossible_beacons(&hm, *target_y, lb, ub),
+                naive_impossible_beacons(&hm, *target_y, 0, span / 2),
+            )
+        },
+        |(input, target_y)| {
+            let hm = parse_input(input);
+            let span = hm.keys().chain(hm.values()).map(|c| c.x).max().unwrap();
+            (
+                find_impossible_beacons(&hm, *target_y, None, None),
+                find_impossible_beacons(&hm, *target_y, Some(0), Some(span / 2)),
+            )
+        },
+    );
+}
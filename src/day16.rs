@@ -2,6 +2,8 @@ use std::collections::{HashMap, HashSet, VecDeque};
 
 use regex::Regex;
 
+use crate::context::{Context, Part};
+
 #[derive(Debug, Clone)]
 struct Room {
     flow: u32,
@@ -51,7 +53,7 @@ fn have_been_here_before(
     }
 }
 
-fn bfs(rooms: &HashMap<String, Room>, start: &str, time_remaining: u8) -> u32 {
+fn bfs(ctx: &Context, rooms: &HashMap<String, Room>, start: &str, time_remaining: u8) -> u32 {
     let mut visited: HashMap<(String, String), Vec<(u8, u32, u32)>> = HashMap::new();
     let mut q = VecDeque::new();
 
@@ -78,7 +80,7 @@ fn bfs(rooms: &HashMap<String, Room>, start: &str, time_remaining: u8) -> u32 {
             route,
         ) = q.pop_front().unwrap();
 
-        println!("Exploring with {time_remaining} left");
+        ctx.diagnostic(format!("Exploring with {time_remaining} left"));
 
         if time_remaining == 0 {
             if pressure_released > max {
@@ -207,12 +209,14 @@ fn bfs(rooms: &HashMap<String, Room>, start: &str, time_remaining: u8) -> u32 {
     max
 }
 
-pub fn day16() {
-    let input = include_str!("../inputs/day16.txt");
+pub fn day16(ctx: &Context) {
+    let input = ctx.input(include_str!("../inputs/day16.txt"));
     let rooms = parse_input(input);
 
-    let max = bfs(&rooms, &"AA", 26);
-    println!("Part B is: {}", max);
+    if ctx.wants(Part::B) {
+        let max = bfs(ctx, &rooms, "AA", 26);
+        ctx.answer(Part::B, max);
+    }
 }
 
 #[test]
@@ -229,6 +233,6 @@ Valve II has flow rate=0; tunnels lead to valves AA, JJ
 Valve JJ has flow rate=21; tunnel leads to valve II"#;
 
     let rooms = parse_input(input);
-    let max = bfs(&rooms, &"AA", 30);
+    let max = bfs(&Context::default(), &rooms, "AA", 30);
     assert_eq!(1651, max);
 }
@@ -1,3 +1,11 @@
+use crate::{
+    context::{Context, Part},
+    validate::Diagnostic,
+};
+
+// Rows the grid can hold before wrapping round onto old rocks.
+const GRID_HEIGHT: usize = 5000;
+
 #[derive(Debug)]
 enum Shape {
     Dash(Coord),
@@ -81,7 +89,7 @@ enum Dir {
 }
 
 struct Grid {
-    heights: [[bool; 5000]; 7],
+    heights: [[bool; GRID_HEIGHT]; 7],
     highest_point: usize,
 }
 
@@ -90,17 +98,17 @@ impl Grid {
         for c in shape.coordinates() {
             match dir {
                 Dir::Left => {
-                    if c.x == 0 || self.heights[c.x - 1][c.y % 5000] {
+                    if c.x == 0 || self.heights[c.x - 1][c.y % GRID_HEIGHT] {
                         return true;
                     }
                 }
                 Dir::Right => {
-                    if c.x == 6 || self.heights[c.x + 1][c.y % 5000] {
+                    if c.x == 6 || self.heights[c.x + 1][c.y % GRID_HEIGHT] {
                         return true;
                     }
                 }
                 Dir::Down => {
-                    if c.y == 0 || self.heights[c.x][(c.y - 1) % 5000] {
+                    if c.y == 0 || self.heights[c.x][(c.y - 1) % GRID_HEIGHT] {
                         return true;
                     }
                 }
@@ -111,7 +119,7 @@ impl Grid {
 
     fn add_shape(&mut self, shape: &Shape) {
         for c in shape.coordinates() {
-            self.heights[c.x][c.y % 5000] = true;
+            self.heights[c.x][c.y % GRID_HEIGHT] = true;
             if c.y > self.highest_point {
                 self.highest_point = c.y;
             }
@@ -130,6 +138,61 @@ fn parse_input(input: &str) -> Vec<Dir> {
         .collect()
 }
 
+// By observation and printlns.
+const FIRST_HEIGHT: usize = 2318;
+
+// By observation and printls
+const WRAP_INFO_SHAPE_START: usize = 1485 + 1;
+const WRAP_INFO_SHAPE_CYCLE: usize = 1700;
+const WRAP_INFO_HEIGHT_CYCLE: usize = 2642;
+const STARTING_JET_INDEX: usize = 8772;
+
+const HUGE: usize = 1000000000000;
+
+// The jets the constants above were observed with: how many there are, and
+// their FNV-1a hash.
+const JET_COUNT: usize = 10091;
+const JET_FINGERPRINT: u64 = 0x6d6f28a9a6a3cc13;
+
+fn fingerprint(jets: &str) -> u64 {
+    jets.bytes().fold(0xcbf29ce484222325, |hash, b| {
+        (hash ^ b as u64).wrapping_mul(0x100000001b3)
+    })
+}
+
+// Jets must all be pushes, and the cycle skipped over was only ever observed
+// for the bundled input's jets - any others would give a wrong answer.
+pub(crate) fn validate(input: &str) -> Vec<Diagnostic> {
+    let mut diagnostics = vec![];
+    if input.is_empty() {
+        diagnostics.push(Diagnostic::new("no jets"));
+    }
+    // Everything is read as a jet - even a trailing newline.
+    if let Some((col, c)) = input
+        .chars()
+        .enumerate()
+        .find(|(_, c)| *c != '<' && *c != '>')
+    {
+        diagnostics.push(Diagnostic::at(
+            0,
+            format!("{:?} at column {} is not a jet", c, col + 1),
+        ));
+    }
+
+    if input.len() != JET_COUNT {
+        diagnostics.push(Diagnostic::new(format!(
+            "the cycle was observed with {} jets, not {}",
+            JET_COUNT,
+            input.len()
+        )));
+    } else if fingerprint(input) != JET_FINGERPRINT {
+        diagnostics.push(Diagnostic::new(
+            "the cycle was observed with different jets",
+        ));
+    }
+    diagnostics
+}
+
 fn drop_shape(shape: &mut Shape, jets: &Vec<Dir>, jet_index: &mut usize, grid: &mut Grid) {
     match shape {
         Shape::Dash(_) | Shape::Plus(_) | Shape::Corner(_) | Shape::Line(_) | Shape::Square(_) => {
@@ -146,13 +209,16 @@ fn drop_shape(shape: &mut Shape, jets: &Vec<Dir>, jet_index: &mut usize, grid: &
     }
 }
 
-pub fn day17() {
-    let input = include_str!("../inputs/day17.txt");
+pub fn day17(ctx: &Context) {
+    let input = ctx.input(include_str!("../inputs/day17.txt"));
+    if let Some(problem) = validate(input).first() {
+        panic!("Bad input: {}", problem);
+    }
 
     let jets = parse_input(input);
 
     let mut grid = Grid {
-        heights: [[false; 5000]; 7],
+        heights: [[false; GRID_HEIGHT]; 7],
         highest_point: 0,
     };
     for i in 0..7 {
@@ -174,28 +240,18 @@ pub fn day17() {
     // - The middle height - calculated by multiplying the cycle height by the amount of cycles
     // - The final height - calculated by just running the old program.
 
-    // By observation and printlns.
-    let first_height = 2318;
-
-    // By observation and printls
-    let wrap_info_shape_start = 1485 + 1;
-    let wrap_info_shape_cycle = 1700;
-    let wrap_info_height_cycle = 2642;
-
-    let huge = 1000000000000;
-    let num_of_cycles = (huge - wrap_info_shape_start) / wrap_info_shape_cycle;
-    let middle_height = wrap_info_height_cycle * num_of_cycles;
-
+    let num_of_cycles = (HUGE - WRAP_INFO_SHAPE_START) / WRAP_INFO_SHAPE_CYCLE;
+    let middle_height = WRAP_INFO_HEIGHT_CYCLE * num_of_cycles;
 
-    let mut jet_index = 8772;
-    let starting_shape = wrap_info_shape_start + num_of_cycles * wrap_info_shape_cycle;
+    let mut jet_index = STARTING_JET_INDEX;
+    let starting_shape = WRAP_INFO_SHAPE_START + num_of_cycles * WRAP_INFO_SHAPE_CYCLE;
 
-    for i in starting_shape..huge {
+    for i in starting_shape..HUGE {
         let mut shape = shapes[i % shapes.len()](Coord {
             x: 2,
             y: grid.highest_point + 4,
         });
         drop_shape(&mut shape, &jets, &mut jet_index, &mut grid);
     }
-    println!("Part B is: {}", first_height + middle_height + grid.highest_point);
+    ctx.answer(Part::B, FIRST_HEIGHT + middle_height + grid.highest_point);
 }
\ No newline at end of file
@@ -1,3 +1,5 @@
+use crate::context::{Context, Part};
+
 #[derive(Debug)]
 struct Coord {
     x: usize,
@@ -25,16 +27,17 @@ fn parse_input(input: &str) -> Vec<Coord> {
         .collect()
 }
 
-fn dfs(grid: &mut Vec<Vec<Vec<Cube>>>, node: Coord) {
+fn dfs(ctx: &Context, grid: &mut Vec<Vec<Vec<Cube>>>, node: Coord) {
     if grid[node.x][node.y][node.z].visited || grid[node.x][node.y][node.z].solid {
         return;
     }
-    println!("Exploring: {},{},{}", node.x, node.y, node.z);
+    ctx.diagnostic(format!("Exploring: {},{},{}", node.x, node.y, node.z));
     grid[node.x][node.y][node.z].visited = true;
 
     // x
     if node.x > 0 {
         dfs(
+            ctx,
             grid,
             Coord {
                 x: node.x - 1,
@@ -45,6 +48,7 @@ fn dfs(grid: &mut Vec<Vec<Vec<Cube>>>, node: Coord) {
     }
     if node.x < grid.len() - 1 {
         dfs(
+            ctx,
             grid,
             Coord {
                 x: node.x + 1,
@@ -56,6 +60,7 @@ fn dfs(grid: &mut Vec<Vec<Vec<Cube>>>, node: Coord) {
     // y
     if node.y > 0 {
         dfs(
+            ctx,
             grid,
             Coord {
                 x: node.x,
@@ -66,6 +71,7 @@ fn dfs(grid: &mut Vec<Vec<Vec<Cube>>>, node: Coord) {
     }
     if node.y < grid[0].len() - 1 {
         dfs(
+            ctx,
             grid,
             Coord {
                 x: node.x,
@@ -77,6 +83,7 @@ fn dfs(grid: &mut Vec<Vec<Vec<Cube>>>, node: Coord) {
     // z
     if node.z > 0 {
         dfs(
+            ctx,
             grid,
             Coord {
                 x: node.x,
@@ -87,6 +94,7 @@ fn dfs(grid: &mut Vec<Vec<Vec<Cube>>>, node: Coord) {
     }
     if node.z < grid[0][0].len() - 1 {
         dfs(
+            ctx,
             grid,
             Coord {
                 x: node.x,
@@ -124,7 +132,7 @@ fn has_visible_face(
     *previously_internal = !c.visited;
 }
 
-fn solve(coords: Vec<Coord>) -> (u32, u32) {
+fn solve(ctx: &Context, coords: Vec<Coord>) -> (u32, u32) {
     let max_x = coords.iter().map(|c| c.x).max().unwrap() + 1;
     let max_y = coords.iter().map(|c| c.y).max().unwrap() + 1;
     let max_z = coords.iter().map(|c| c.z).max().unwrap() + 1;
@@ -151,6 +159,7 @@ fn solve(coords: Vec<Coord>) -> (u32, u32) {
 
     // Discover all the external squares - assume they're all connected to the edge
     dfs(
+        ctx,
         &mut grid,
         Coord {
             x: max_x - 1,
@@ -249,16 +258,16 @@ fn solve(coords: Vec<Coord>) -> (u32, u32) {
         }
     }
 
-    println!("Part A is: {}", part_a);
-    println!("Part B is: {}", part_b);
     (part_a, part_b)
 }
 
-pub fn day18() {
-    let input = include_str!("../inputs/day18.txt");
+pub fn day18(ctx: &Context) {
+    let input = ctx.input(include_str!("../inputs/day18.txt"));
     let coords = parse_input(input);
 
-    _ = solve(coords)
+    let (part_a, part_b) = solve(ctx, coords);
+    ctx.answer(Part::A, part_a);
+    ctx.answer(Part::B, part_b);
 }
 
 #[test]
@@ -278,7 +287,7 @@ fn sample_input() {
 2,3,5"#;
 
     let cs = parse_input(input);
-    let (part_a, part_b) = solve(cs);
+    let (part_a, part_b) = solve(&Context::default(), cs);
     assert_eq!(part_a, 64);
     assert_eq!(part_b, 58);
 }
@@ -293,7 +302,7 @@ fn c_shape() {
 3,3,0"#;
 
     let cs = parse_input(input);
-    let (part_a, part_b) = solve(cs);
+    let (part_a, part_b) = solve(&Context::default(), cs);
     assert_eq!(part_a, 26);
     assert_eq!(part_b, 16);
 }
@@ -1,7 +1,12 @@
 use std::{collections::HashSet, hash::Hash};
 
+#[cfg(test)]
+use std::collections::HashMap;
+
 use regex::Regex;
 
+use crate::context::{Context, Part};
+
 #[derive(Debug, Clone, Hash, Eq, PartialEq, Copy)]
 enum Material {
     Ore,
@@ -202,6 +207,87 @@ fn recurse(
     max
 }
 
+// Tries building every affordable robot (or nothing) every turn, remembering
+// the states already seen. Only feasible for a small number of turns.
+#[cfg(test)]
+fn naive_max_geodes(b: &Blueprint, turns: u32) -> u32 {
+    type State = (u32, [u32; 4], [u32; 4]);
+
+    fn explore(
+        b: &Blueprint,
+        turns: u32,
+        i: Inventory,
+        w: Workforce,
+        seen: &mut HashMap<State, u32>,
+    ) -> u32 {
+        if turns == 0 {
+            return i.geode;
+        }
+        let key = (
+            turns,
+            [i.ore, i.clay, i.obsidian, i.geode],
+            [
+                w.ore_producers,
+                w.clay_producers,
+                w.obsidian_producers,
+                w.geode_producers,
+            ],
+        );
+        if let Some(&geodes) = seen.get(&key) {
+            return geodes;
+        }
+
+        let affordable = [
+            (Material::Noop, true),
+            (Material::Ore, b.ore_cost <= i.ore),
+            (Material::Clay, b.clay_cost <= i.ore),
+            (
+                Material::Obsidian,
+                b.obsidian_cost_ore_clay.0 <= i.ore && b.obsidian_cost_ore_clay.1 <= i.clay,
+            ),
+            (
+                Material::Geode,
+                b.geode_cost_ore_obsidian.0 <= i.ore && b.geode_cost_ore_obsidian.1 <= i.obsidian,
+            ),
+        ];
+        let mut collected = i.clone();
+        accumulate(&mut collected, &w);
+
+        let mut best = 0;
+        for (action, _) in affordable.iter().filter(|(_, can)| *can) {
+            let mut candidate_inventory = collected.clone();
+            let mut candidate_workforce = w.clone();
+            take_action(
+                b,
+                &mut candidate_inventory,
+                &mut candidate_workforce,
+                action,
+            );
+            best = best.max(explore(
+                b,
+                turns - 1,
+                candidate_inventory,
+                candidate_workforce,
+                seen,
+            ));
+        }
+        seen.insert(key, best);
+        best
+    }
+
+    let w = Workforce {
+        ore_producers: 1,
+        ..Default::default()
+    };
+    let i = Inventory {
+        ore: 0,
+        clay: 0,
+        obsidian: 0,
+        geode: 0,
+    };
+    explore(b, turns, i, w, &mut HashMap::new())
+}
+
 fn solve_blueprint(b: &Blueprint, turns: &mut u32) -> u32 {
     let mut w = Workforce {
         ore_producers: 1,
@@ -230,30 +316,33 @@ fn solve_blueprint(b: &Blueprint, turns: &mut u32) -> u32 {
     )
 }
 
-pub fn day19() {
-    let input = include_str!("../inputs/day19.txt");
+pub fn day19(ctx: &Context) {
+    let input = ctx.input(include_str!("../inputs/day19.txt"));
     let bs = parse_input(input);
 
-    let mut part_a = 0;
-    for (i, b) in bs.iter().enumerate() {
-        let mut turns = 24;
-        println!("> Blueprint {}:", i + 1);
-        println!("{:?}", b);
-        let geodes = solve_blueprint(&b, &mut turns);
-        part_a += i as u32 * geodes as u32;
+    if ctx.wants(Part::A) {
+        let mut part_a = 0;
+        for (i, b) in bs.iter().enumerate() {
+            let mut turns = 24;
+            ctx.diagnostic(format!("> Blueprint {}:", i + 1));
+            ctx.diagnostic(format!("{:?}", b));
+            let geodes = solve_blueprint(b, &mut turns);
+            part_a += i as u32 * geodes;
+        }
+        ctx.answer(Part::A, part_a);
     }
-    println!("Part A is: {:?}", part_a);
-
-    let mut part_b = 1;
-    for (i, b) in bs.iter().take(3).enumerate() {
-        let mut turns = 32;
-        println!("> Blueprint {}:", i + 1);
-        println!("{:?}", b);
-        let geodes = solve_blueprint(&b, &mut turns);
-        part_b *= geodes as u32;
+
+    if ctx.wants(Part::B) {
+        let mut part_b = 1;
+        for (i, b) in bs.iter().take(3).enumerate() {
+            let mut turns = 32;
+            ctx.diagnostic(format!("> Blueprint {}:", i + 1));
+            ctx.diagnostic(format!("{:?}", b));
+            let geodes = solve_blueprint(b, &mut turns);
+            part_b *= geodes;
+        }
+        ctx.answer(Part::B, part_b);
     }
-    assert!(part_b > 1664);
-    println!("Part B is: {:?}", part_b)
 }
 
 #[test]
@@ -288,3 +377,23 @@ fn real_input_30() {
     let geodes = solve_blueprint(&b[0], &mut turns);
     assert_eq!(geodes, 0);
 }
+
+#[test]
+fn pruning_matches_naive() {
+    crate::differential::check(
+        30,
+        |rng| {
+            let input = crate::generate::generate(19, rng.next_u64(), 1).unwrap();
+            (input, rng.range(12, 19) as u32)
+        },
+        |(input, turns)| {
+            if *turns > 1 {
+                vec![(input.clone(), turns - 1)]
+            } else {
+                vec![]
+            }
+        },
+        |(input, turns)| naive_max_geodes(&parse_input(input)[0], *turns),
+        |(input, turns)| solve_blueprint(&parse_input(input)[0], &mut turns.clone()),
+    );
+}
@@ -1,3 +1,5 @@
+use crate::context::{Context, Part};
+
 #[derive(Debug, Copy, Clone)]
 struct N {
     original_pos: usize,
@@ -67,12 +69,12 @@ fn solve(nums: &mut Vec<N>) -> (i64, i64) {
     (part_a, calculate_answer(nums))
 }
 
-pub fn day20() {
-    let input = include_str!("../inputs/day20.txt");
+pub fn day20(ctx: &Context) {
+    let input = ctx.input(include_str!("../inputs/day20.txt"));
     let mut nums: Vec<N> = parse_input(input);
     let (a, b) = solve(&mut nums);
-    println!("Part A is: {}", a);
-    println!("Part B is: {}", b);
+    ctx.answer(Part::A, a);
+    ctx.answer(Part::B, b);
 }
 
 #[test]
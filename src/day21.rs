@@ -2,6 +2,8 @@ use std::collections::HashMap;
 
 use regex::Regex;
 
+use crate::context::{Context, Part};
+
 #[derive(Debug)]
 enum Monkey {
     Val(i64),
@@ -119,14 +121,18 @@ fn solve_part_b(monkeys: &HashMap<String, Monkey>) -> i64 {
     humn
 }
 
-pub fn day21() {
-    let input = include_str!("../inputs/day21.txt");
+pub fn day21(ctx: &Context) {
+    let input = ctx.input(include_str!("../inputs/day21.txt"));
 
     let monkeys = parse_input(input);
-    let val = calculate_value(&monkeys, "root");
-    println!("Part A is: {}", val);
+    if ctx.wants(Part::A) {
+        let val = calculate_value(&monkeys, "root");
+        ctx.answer(Part::A, val);
+    }
 
-    println!("Part B is: {}", solve_part_b(&monkeys));
+    if ctx.wants(Part::B) {
+        ctx.answer(Part::B, solve_part_b(&monkeys));
+    }
 }
 
 #[test]
@@ -2,6 +2,11 @@ use std::collections::HashMap;
 
 use regex::Regex;
 
+use crate::{
+    context::{Context, Part},
+    validate::Diagnostic,
+};
+
 #[derive(Debug)]
 enum Dir {
     R,
@@ -79,7 +84,10 @@ impl Face {
     }
 }
 
-fn parse_input(input: &str) -> (HashMap<(usize, usize), Face>, Vec<Instruction>, usize) {
+fn parse_input(
+    ctx: &Context,
+    input: &str,
+) -> (HashMap<(usize, usize), Face>, Vec<Instruction>, usize) {
     let face_dimension = ((input
         .lines()
         .take_while(|&s| s != "")
@@ -97,7 +105,7 @@ fn parse_input(input: &str) -> (HashMap<(usize, usize), Face>, Vec<Instruction>,
             if y * 50 < c.len() {
                 if x * 50 < c[y * 50].len() {
                     if c[y * 50].chars().nth(x * 50).unwrap() != ' ' {
-                        println!("Cube at coords: {},{}", x, y);
+                        ctx.diagnostic(format!("Cube at coords: {},{}", x, y));
                         let mut face = vec![];
                         // Construct column then rows, to make navigation easier later.
                         for i in 0..50 {
@@ -129,6 +137,91 @@ fn parse_input(input: &str) -> (HashMap<(usize, usize), Face>, Vec<Instruction>,
     (net, i, face_dimension)
 }
 
+// `parse_input` cuts the map into 50x50 faces and `find_next_pos` only knows
+// how to wrap between faces laid out like this.
+pub(crate) const FACE_SIZE: usize = 50;
+pub(crate) const NET: [(usize, usize); 6] = [(1, 0), (2, 0), (1, 1), (0, 2), (1, 2), (0, 3)];
+
+pub(crate) fn validate(input: &str) -> Vec<Diagnostic> {
+    let mut diagnostics = vec![];
+    let lines = input.lines().collect::<Vec<_>>();
+    let map = match lines.iter().position(|l| l.is_empty()) {
+        Some(blank) => &lines[..blank],
+        None => {
+            return vec![Diagnostic::new(
+                "no blank line between the map and the path",
+            )]
+        }
+    };
+
+    for (i, line) in map.iter().enumerate() {
+        if let Some(c) = line.chars().find(|c| !" .#".contains(*c)) {
+            diagnostics.push(Diagnostic::at(i, format!("{:?} is not part of a map", c)));
+        }
+        if line.len() > 5 * FACE_SIZE {
+            diagnostics.push(Diagnostic::at(
+                i,
+                format!("{} columns won't fit a 5x5 net", line.len()),
+            ));
+        }
+    }
+    if map.len() > 5 * FACE_SIZE {
+        diagnostics.push(Diagnostic::new(format!(
+            "{} rows won't fit a 5x5 net",
+            map.len()
+        )));
+    }
+
+    let tiles = map.iter().map(|l| l.trim().len()).sum::<usize>();
+    if tiles != 6 * FACE_SIZE * FACE_SIZE {
+        diagnostics.push(Diagnostic::new(format!(
+            "{} tiles, but six {}x{} faces need {}",
+            tiles,
+            FACE_SIZE,
+            FACE_SIZE,
+            6 * FACE_SIZE * FACE_SIZE
+        )));
+    }
+
+    let mut faces = vec![];
+    for y in 0..5 {
+        for x in 0..5 {
+            let filled = (y * FACE_SIZE..(y + 1) * FACE_SIZE)
+                .flat_map(|row| (x * FACE_SIZE..(x + 1) * FACE_SIZE).map(move |col| (row, col)))
+                .filter(|&(row, col)| {
+                    map.get(row)
+                        .and_then(|l| l.as_bytes().get(col))
+                        .is_some_and(|c| *c != b' ')
+                })
+                .count();
+            if filled == FACE_SIZE * FACE_SIZE {
+                faces.push((x, y));
+            } else if filled > 0 {
+                diagnostics.push(Diagnostic::at(
+                    y * FACE_SIZE,
+                    format!("face ({}, {}) is only partly filled", x, y),
+                ));
+            }
+        }
+    }
+    faces.sort_by_key(|&(x, y)| (y, x));
+    if diagnostics.is_empty() && faces != NET {
+        diagnostics.push(Diagnostic::new(format!(
+            "faces at {:?}, but wrapping is only known for {:?}",
+            faces, NET
+        )));
+    }
+
+    let path = lines.last().unwrap_or(&"");
+    if !Regex::new(r"^(\d+|[LR])+$").unwrap().is_match(path) {
+        diagnostics.push(Diagnostic::at(
+            lines.len().saturating_sub(1),
+            "the path should be steps and L/R turns",
+        ));
+    }
+    diagnostics
+}
+
 // This is the interesting function.
 // TODO - this is hardcoded - too hard to compute for all the various nets!?
 fn find_next_pos(
@@ -414,9 +507,9 @@ fn get_face(faces: &HashMap<(usize, usize), Face>, id: (usize, usize)) -> &Face
     faces.get(&id).unwrap()
 }
 
-pub fn day22() {
-    let input = include_str!("../inputs/day22.txt");
-    let (faces, instructions, dimension) = parse_input(input);
+pub fn day22(ctx: &Context) {
+    let input = ctx.input(include_str!("../inputs/day22.txt"));
+    let (faces, instructions, dimension) = parse_input(ctx, input);
 
     // TODO - hardcoded knowledge of where the first face is!
     let mut pos = Pos {
@@ -506,8 +599,8 @@ pub fn day22() {
     }
     let (a_x, a_y) = pos.face_id;
     let (real_x, real_y) = (a_x * 50 + pos.coord.x, a_y * 50 + pos.coord.y);
-    println!(
-        "Part B is {}",
-        (real_y + 1) * 1000 + (real_x + 1) * 4 + dir as usize
+    ctx.answer(
+        Part::B,
+        (real_y + 1) * 1000 + (real_x + 1) * 4 + dir as usize,
     );
 }
@@ -1,5 +1,7 @@
 use std::collections::{HashMap, HashSet};
 
+use crate::context::{Context, Part};
+
 #[derive(Debug, Hash, Eq, PartialEq, Copy, Clone)]
 struct Coord {
     x: i32,
@@ -198,7 +200,7 @@ fn grid_size(elves: &HashSet<Coord>) -> (i32, i32, i32, i32) {
 //     }
 // }
 
-fn solve(_elves: HashSet<Coord>) -> (i32, i32) {
+fn solve(ctx: &Context, _elves: HashSet<Coord>) -> (i32, i32) {
     let mut elves = _elves.clone();
     let mut dirs = [try_north, try_south, try_west, try_east];
     let rounds_a = 10;
@@ -236,7 +238,7 @@ fn solve(_elves: HashSet<Coord>) -> (i32, i32) {
         elves = new_elves.keys().cloned().collect();
         dirs.rotate_left(1);
 
-        println!("Done round: {}", round);
+        ctx.diagnostic(format!("Done round: {}", round));
 
         if round == rounds_a {
             let (min_x, max_x, min_y, max_y) = grid_size(&elves);
@@ -247,12 +249,12 @@ fn solve(_elves: HashSet<Coord>) -> (i32, i32) {
     (part_a, part_b)
 }
 
-pub fn day23() {
-    let input = include_str!("../inputs/day23.txt");
+pub fn day23(ctx: &Context) {
+    let input = ctx.input(include_str!("../inputs/day23.txt"));
     let elves = parse_input(input);
-    let (a, b) = solve(elves);
-    println!("Part A is: {}", a);
-    println!("Part B is: {}", b);
+    let (a, b) = solve(ctx, elves);
+    ctx.answer(Part::A, a);
+    ctx.answer(Part::B, b);
 }
 
 #[test]
@@ -271,7 +273,7 @@ fn sample_input() {
 .............."#;
 
     let elves = parse_input(input);
-    let (a, b) = solve(elves);
+    let (a, b) = solve(&Context::default(), elves);
     assert_eq!(a, 110);
     assert_eq!(b, 20);
 }
@@ -1,5 +1,10 @@
 use std::collections::{BTreeSet, VecDeque};
 
+use crate::{
+    context::{Context, Part},
+    validate::Diagnostic,
+};
+
 #[derive(Debug, Hash, PartialEq, Eq, Clone, Copy, PartialOrd, Ord)]
 struct Coord {
     x: usize,
@@ -131,6 +136,42 @@ impl Grid {
     }
 }
 
+// Moves every blizzard one step at a time until the given minute.
+#[cfg(test)]
+fn naive_blizzards(input: &str, minute: usize) -> BTreeSet<Coord> {
+    let height = input.lines().count();
+    let width = input.lines().next().unwrap().len();
+    let mut blizzards = vec![];
+    for (y, s) in input.lines().enumerate() {
+        for (x, c) in s.chars().enumerate() {
+            if "<>^v".contains(c) {
+                blizzards.push((Coord { x, y }, c));
+            }
+        }
+    }
+    for _ in 0..minute {
+        for (pos, dir) in blizzards.iter_mut() {
+            *pos = match dir {
+                '>' if pos.x == width - 2 => Coord { x: 1, y: pos.y },
+                '<' if pos.x == 1 => Coord {
+                    x: width - 2,
+                    y: pos.y,
+                },
+                'v' if pos.y == height - 2 => Coord { x: pos.x, y: 1 },
+                '^' if pos.y == 1 => Coord {
+                    x: pos.x,
+                    y: height - 2,
+                },
+                '>' => pos.e(),
+                '<' => pos.w(),
+                'v' => pos.s(),
+                _ => pos.n(),
+            };
+        }
+    }
+    blizzards.into_iter().map(|(pos, _)| pos).collect()
+}
+
 fn bfs(grid: &mut Grid, start: &Coord, end: &Coord, initial_day: usize) -> usize {
     let mut visited = BTreeSet::new();
     let mut q = VecDeque::new();
@@ -158,7 +199,6 @@ fn bfs(grid: &mut Grid, start: &Coord, end: &Coord, initial_day: usize) -> usize
 }
 
 fn parse_input(input: &str) -> Grid {
-    println!("Parsing input");
     let mut blizzard_positions_up = BTreeSet::new();
     let mut blizzard_positions_down = BTreeSet::new();
     let mut blizzard_positions_left = BTreeSet::new();
@@ -194,8 +234,65 @@ fn parse_input(input: &str) -> Grid {
     }
 }
 
-pub fn day24() {
-    let input = include_str!("../inputs/day24.txt");
+// The solver walks from (1, 0) to (width - 2, height - 1) and wraps blizzards
+// inside the walls, so the valley has to be walled in with just those two gaps.
+pub(crate) fn validate(input: &str) -> Vec<Diagnostic> {
+    let lines = input.lines().map(|l| l.as_bytes()).collect::<Vec<_>>();
+    let (height, width) = (lines.len(), lines.first().map_or(0, |l| l.len()));
+    if height < 3 || width < 4 {
+        return vec![Diagnostic::new(format!(
+            "a {}x{} valley is too small",
+            width, height
+        ))];
+    }
+
+    let mut diagnostics = vec![];
+    for (y, line) in lines.iter().enumerate() {
+        if line.len() != width {
+            diagnostics.push(Diagnostic::at(
+                y,
+                format!("{} wide, but the first row is {}", line.len(), width),
+            ));
+            continue;
+        }
+        for (x, &c) in line.iter().enumerate() {
+            let gap = (x, y) == (1, 0) || (x, y) == (width - 2, height - 1);
+            let wall = x == 0 || y == 0 || x == width - 1 || y == height - 1;
+            let expected: &[u8] = if gap {
+                b"."
+            } else if wall {
+                b"#"
+            } else {
+                b".<>^v"
+            };
+            if !expected.contains(&c) {
+                diagnostics.push(Diagnostic::at(
+                    y,
+                    format!(
+                        "{:?} at column {} - expected one of {:?}",
+                        c as char,
+                        x + 1,
+                        String::from_utf8_lossy(expected)
+                    ),
+                ));
+            }
+            if (x == 1 || x == width - 2) && (c == b'^' || c == b'v') {
+                diagnostics.push(Diagnostic::at(
+                    y,
+                    format!(
+                        "vertical blizzard at column {} would blow out of the valley",
+                        x + 1
+                    ),
+                ));
+            }
+        }
+    }
+    diagnostics
+}
+
+pub fn day24(ctx: &Context) {
+    let input = ctx.input(include_str!("../inputs/day24.txt"));
+    ctx.diagnostic("Parsing input");
     let mut grid = parse_input(input);
 
     let height = grid.height;
@@ -228,8 +325,8 @@ pub fn day24() {
         },
         back - 1,
     );
-    println!("Part A is {:?}", there - 1);
-    println!("Part B is {:?}", there_again - 1);
+    ctx.answer(Part::A, there - 1);
+    ctx.answer(Part::B, there_again - 1);
 }
 
 #[test]
@@ -280,3 +377,73 @@ fn sample_input_ajw3() {
 
     assert_eq!(54, there_again - 1);
 }
+
+#[test]
+fn blizzards_match_naive() {
+    let inner = |input: &str| {
+        let (height, width) = (input.lines().count(), input.lines().next().unwrap().len());
+        (1..height - 1).flat_map(move |y| (1..width - 1).map(move |x| Coord { x, y }))
+    };
+    crate::differential::check(
+        50,
+        |rng| crate::generate::generate(24, rng.next_u64(), rng.range(1, 4) as usize).unwrap(),
+        |input: &String| {
+            let lines = input.lines().collect::<Vec<_>>();
+            let (height, width) = (lines.len(), lines[0].len());
+            let mut smaller = vec![];
+            // Drop inner rows and columns, leaving the entry and exit columns alone.
+            for y in 1..height - 1 {
+                if height > 3 {
+                    let mut fewer = lines.clone();
+                    fewer.remove(y);
+                    smaller.push(fewer.join("\n"));
+                }
+            }
+            for x in 2..width - 2 {
+                smaller.push(
+                    lines
+                        .iter()
+                        .map(|l| format!("{}{}", &l[..x], &l[x + 1..]))
+                        .collect::<Vec<_>>()
+                        .join("\n"),
+                );
+            }
+            // Calm each blizzard in turn.
+            for (i, c) in input.char_indices() {
+                if "<>^v".contains(c) {
+                    smaller.push(format!("{}.{}", &input[..i], &input[i + 1..]));
+                }
+            }
+            smaller
+        },
+        |input| {
+            let (height, width) = (input.lines().count(), input.lines().next().unwrap().len());
+            (0..2 * (height - 2) * (width - 2))
+                .map(|minute| naive_blizzards(input, minute))
+                .collect::<Vec<_>>()
+        },
+        |input| {
+            let grid = parse_input(input);
+            let (height, width) = (grid.height, grid.width);
+            (0..2 * (height - 2) * (width - 2))
+                .map(|minute| {
+                    inner(input)
+                        .filter(|c| grid.contains_blizzard(c, minute))
+                        .collect()
+                })
+                .collect::<Vec<_>>()
+        },
+    );
+}
+
+#[test]
+fn validate_moved_entry() {
+    let input = "##.###\n#>.<.#\n####.#";
+    assert_eq!(
+        vec![
+            Diagnostic::at(0, "'#' at column 2 - expected one of \".\""),
+            Diagnostic::at(0, "'.' at column 3 - expected one of \"#\""),
+        ],
+        validate(input)
+    );
+}
@@ -1,4 +1,6 @@
-use std::fmt::Debug;
+use std::fmt::{Debug, Display};
+
+use crate::context::{Context, Part};
 
 enum SnafuUnit {
     Two,
@@ -54,6 +56,15 @@ impl Debug for Snafu {
     }
 }
 
+impl Display for Snafu {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        for unit in &self.val {
+            write!(f, "{:?}", unit)?;
+        }
+        Ok(())
+    }
+}
+
 fn to_snafu(i: i64) -> Snafu {
     // This is naff but it's Christmas!
     let mut ord_index = 0;
@@ -103,12 +114,12 @@ fn parse_input(input: &str) -> Vec<Snafu> {
         .collect()
 }
 
-pub fn day25() {
-    let input = include_str!("../inputs/day25.txt");
+pub fn day25(ctx: &Context) {
+    let input = ctx.input(include_str!("../inputs/day25.txt"));
     let snafus = parse_input(input);
 
     let snafu_sum = snafus.iter().map(|s| s.to_decimal()).sum::<i64>();
-    println!("Part A is {:?}", to_snafu(snafu_sum));
+    ctx.answer(Part::A, to_snafu(snafu_sum));
 }
 
 #[test]
@@ -0,0 +1,109 @@
+use std::{
+    fmt::Debug,
+    panic::{self, AssertUnwindSafe},
+};
+
+use crate::generate::Rng;
+
+// Runs a naive reference solver and an optimized solver over `cases` generated
+// inputs. The first input they disagree on (or that makes the optimized solver
+// panic) is shrunk to the smallest one that still disagrees, then reported.
+pub fn check<I, O>(
+    cases: u64,
+    generate: impl Fn(&mut Rng) -> I,
+    shrink: impl Fn(&I) -> Vec<I>,
+    reference: impl Fn(&I) -> O,
+    optimized: impl Fn(&I) -> O,
+) where
+    I: Debug,
+    O: PartialEq + Debug,
+{
+    let run_optimized = |input: &I| panic::catch_unwind(AssertUnwindSafe(|| optimized(input)));
+    let disagrees = |input: &I| match run_optimized(input) {
+        Ok(actual) => actual != reference(input),
+        Err(_) => true,
+    };
+
+    for seed in 0..cases {
+        let mut input = generate(&mut Rng::new(seed));
+        if !disagrees(&input) {
+            continue;
+        }
+
+        // Greedily take the first smaller input that still fails, until none do.
+        'shrinking: loop {
+            for candidate in shrink(&input) {
+                if disagrees(&candidate) {
+                    input = candidate;
+                    continue 'shrinking;
+                }
+            }
+            break;
+        }
+
+        let actual = match run_optimized(&input) {
+            Ok(actual) => format!("{:?}", actual),
+            Err(_) => "panicked".to_string(),
+        };
+        panic!(
+            "Solvers disagree (seed {}) on minimal input:\n{:?}\nreference: {:?}\noptimized: {}",
+            seed,
+            input,
+            reference(&input),
+            actual
+        );
+    }
+}
+
+// Every way of dropping a single line, keeping at least `keep` lines.
+pub fn without_each_line(input: &str, keep: usize) -> Vec<String> {
+    let lines = input.lines().collect::<Vec<_>>();
+    if lines.len() <= keep {
+        return vec![];
+    }
+    (0..lines.len())
+        .map(|i| {
+            let mut fewer = lines.clone();
+            fewer.remove(i);
+            fewer.join("\n")
+        })
+        .collect()
+}
+
+// Every way of dropping a single column from a grid, keeping at least `keep`.
+pub fn without_each_column(input: &str, keep: usize) -> Vec<String> {
+    let width = input.lines().next().map_or(0, |l| l.len());
+    if width <= keep {
+        return vec![];
+    }
+    (0..width)
+        .map(|c| {
+            input
+                .lines()
+                .map(|l| format!("{}{}", &l[..c], &l[c + 1..]))
+                .collect::<Vec<_>>()
+                .join("\n")
+        })
+        .collect()
+}
+
+#[test]
+fn shrinks_to_minimal_input() {
+    // A "solver" that is wrong whenever there are at least two 7s.
+    let result = panic::catch_unwind(|| {
+        check(
+            10,
+            |rng| {
+                (0..20)
+                    .map(|_| rng.range(0, 9).to_string())
+                    .collect::<Vec<_>>()
+                    .join("\n")
+            },
+            |input: &String| without_each_line(input, 0),
+            |input| input.lines().count(),
+            |input| input.lines().count() + (input.matches('7').count() >= 2) as usize,
+        )
+    });
+    let message = *result.unwrap_err().downcast::<String>().unwrap();
+    assert!(message.contains("input:\n\"7\\n7\""), "{}", message);
+}
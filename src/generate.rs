@@ -0,0 +1,833 @@
+use std::collections::{BTreeSet, HashSet};
+
+use crate::day22;
+
+// Small splitmix64 generator. Hand-rolled so that a given seed produces the
+// same input forever, whatever happens to the crates we depend on.
+pub struct Rng(u64);
+
+impl Rng {
+    pub fn new(seed: u64) -> Rng {
+        Rng(seed)
+    }
+
+    pub fn next_u64(&mut self) -> u64 {
+        self.0 = self.0.wrapping_add(0x9E37_79B9_7F4A_7C15);
+        let mut z = self.0;
+        z = (z ^ (z >> 30)).wrapping_mul(0xBF58_476D_1CE4_E5B9);
+        z = (z ^ (z >> 27)).wrapping_mul(0x94D0_49BB_1331_11EB);
+        z ^ (z >> 31)
+    }
+
+    // Uniform in [0, n).
+    pub fn below(&mut self, n: usize) -> usize {
+        (self.next_u64() % n as u64) as usize
+    }
+
+    // Uniform in [lo, hi] - both ends inclusive.
+    pub fn range(&mut self, lo: i64, hi: i64) -> i64 {
+        lo + (self.next_u64() % (hi - lo + 1) as u64) as i64
+    }
+
+    // True with probability percent/100.
+    pub fn chance(&mut self, percent: u64) -> bool {
+        self.next_u64() % 100 < percent
+    }
+
+    pub fn pick<'a, T>(&mut self, items: &'a [T]) -> &'a T {
+        &items[self.below(items.len())]
+    }
+
+    pub fn shuffle<T>(&mut self, items: &mut [T]) {
+        for i in (1..items.len()).rev() {
+            items.swap(i, self.below(i + 1));
+        }
+    }
+}
+
+// Produces a puzzle input for the given day. What `size` means depends on the
+// day - usually the number of lines or records, see each generator.
+pub fn generate(day: u8, seed: u64, size: usize) -> Option<String> {
+    let rng = &mut Rng::new(seed);
+    let size = size.max(1);
+    let input = match day {
+        1 => day01(rng, size),
+        2 => day02(rng, size),
+        3 => day03(rng, size),
+        4 => day04(rng, size),
+        5 => day05(rng, size),
+        6 => day06(rng, size),
+        7 => day07(rng, size),
+        8 => day08(rng, size),
+        9 => day09(rng, size),
+        10 => day10(rng, size),
+        11 => day11(rng, size),
+        12 => day12(rng, size),
+        13 => day13(rng, size),
+        14 => day14(rng, size),
+        15 => day15(rng, size),
+        16 => day16(rng, size),
+        17 => day17(rng, size),
+        18 => day18(rng, size),
+        19 => day19(rng, size),
+        20 => day20(rng, size),
+        21 => day21(rng, size),
+        22 => day22(rng, size),
+        23 => day23(rng, size),
+        24 => day24(rng, size),
+        25 => day25(rng, size),
+        _ => return None,
+    };
+    Some(input)
+}
+
+// `size` elves, each carrying a handful of snacks.
+fn day01(rng: &mut Rng, size: usize) -> String {
+    (0..size)
+        .map(|_| {
+            (0..rng.range(1, 14))
+                .map(|_| rng.range(1000, 70000).to_string())
+                .collect::<Vec<_>>()
+                .join("\n")
+        })
+        .collect::<Vec<_>>()
+        .join("\n\n")
+}
+
+// `size` rounds of the strategy guide.
+fn day02(rng: &mut Rng, size: usize) -> String {
+    (0..size)
+        .map(|_| {
+            format!(
+                "{} {}",
+                rng.pick(&['A', 'B', 'C']),
+                rng.pick(&['X', 'Y', 'Z'])
+            )
+        })
+        .collect::<Vec<_>>()
+        .join("\n")
+}
+
+fn item(priority: usize) -> char {
+    if priority <= 26 {
+        (b'a' + priority as u8 - 1) as char
+    } else {
+        (b'A' + priority as u8 - 27) as char
+    }
+}
+
+// `size` rucksacks, rounded up to whole groups of three. Each rucksack's halves
+// share exactly one item, and each group shares exactly one badge.
+fn day03(rng: &mut Rng, size: usize) -> String {
+    let mut rucksacks = vec![];
+    for _ in 0..size.div_ceil(3) {
+        let badge = rng.range(1, 52) as usize;
+        for elf in 0..3 {
+            // Every other item is kept away from one elf, so only the badge is common.
+            let mut pool = (1..=52)
+                .filter(|&p| p != badge && p % 3 != elf)
+                .collect::<Vec<_>>();
+            rng.shuffle(&mut pool);
+
+            let half = rng.range(3, 12) as usize;
+            let shared = if rng.chance(20) {
+                badge
+            } else {
+                pool.pop().unwrap()
+            };
+            let mut left = vec![shared];
+            let mut right = vec![shared];
+            if shared != badge {
+                if rng.chance(50) {
+                    left.push(badge)
+                } else {
+                    right.push(badge)
+                }
+            }
+            while left.len() < half {
+                left.push(pool.pop().unwrap());
+            }
+            while right.len() < half {
+                right.push(pool.pop().unwrap());
+            }
+            rng.shuffle(&mut left);
+            rng.shuffle(&mut right);
+            rucksacks.push(left.into_iter().chain(right).map(item).collect::<String>());
+        }
+    }
+    rucksacks.join("\n")
+}
+
+// `size` pairs of section assignments.
+fn day04(rng: &mut Rng, size: usize) -> String {
+    let assignment = |rng: &mut Rng| {
+        let a = rng.range(1, 99);
+        let b = rng.range(1, 99);
+        format!("{}-{}", a.min(b), a.max(b))
+    };
+    (0..size)
+        .map(|_| format!("{},{}", assignment(rng), assignment(rng)))
+        .collect::<Vec<_>>()
+        .join("\n")
+}
+
+// A drawing of a few random stacks, then `size` moves that are valid against
+// it. No stack is ever emptied, so there is always a crate on top to report.
+fn day05(rng: &mut Rng, size: usize) -> String {
+    let stacks = (0..rng.range(2, 9))
+        .map(|_| {
+            (0..rng.range(2, 8))
+                .map(|_| (b'A' + rng.below(26) as u8) as char)
+                .collect::<Vec<_>>()
+        })
+        .collect::<Vec<_>>();
+    let mut heights = stacks.iter().map(|s| s.len()).collect::<Vec<_>>();
+    let mut moves = vec![];
+    for _ in 0..size {
+        let spare = (0..heights.len())
+            .filter(|&i| heights[i] > 1)
+            .collect::<Vec<_>>();
+        let from = *rng.pick(&spare);
+        let mut to = rng.below(heights.len() - 1);
+        if to >= from {
+            to += 1;
+        }
+        let count = rng.range(1, (heights[from] - 1).min(8) as i64) as usize;
+        heights[from] -= count;
+        heights[to] += count;
+        moves.push(format!("move {} from {} to {}", count, from + 1, to + 1));
+    }
+    format!("{}\n\n{}", crate::day05::draw(&stacks), moves.join("\n"))
+}
+
+// A stream of `size` characters (at least 20) with a single run of 14 distinct
+// characters somewhere in it. Before the run only three letters are used, so
+// neither marker can appear early.
+fn day06(rng: &mut Rng, size: usize) -> String {
+    let size = size.max(20);
+    let start = rng.below(size - 14 + 1);
+    let mut alphabet = ('a'..='z').collect::<Vec<_>>();
+    rng.shuffle(&mut alphabet);
+
+    let mut stream = String::new();
+    for _ in 0..start {
+        stream.push(*rng.pick(&alphabet[..3]));
+    }
+    stream.extend(&alphabet[..14]);
+    while stream.len() < size {
+        stream.push(*rng.pick(&alphabet));
+    }
+    stream
+}
+
+enum Node {
+    File(String, u64),
+    Dir(String, Vec<Node>),
+}
+
+fn name(rng: &mut Rng, taken: &mut HashSet<String>) -> String {
+    loop {
+        let mut n = (0..rng.range(1, 8))
+            .map(|_| (b'a' + rng.below(26) as u8) as char)
+            .collect::<String>();
+        if rng.chance(40) {
+            let extension = rng.pick(&["txt", "dat", "log", "lst", "ext"]);
+            n.push('.');
+            n.push_str(extension);
+        }
+        if taken.insert(n.clone()) {
+            return n;
+        }
+    }
+}
+
+fn write_transcript(node: &Node, scale: f64, lines: &mut Vec<String>) {
+    if let Node::Dir(_, children) = node {
+        lines.push("$ ls".to_string());
+        for c in children {
+            match c {
+                Node::File(n, s) => {
+                    lines.push(format!("{} {}", (*s as f64 * scale).max(1.0) as u64, n))
+                }
+                Node::Dir(n, _) => lines.push(format!("dir {}", n)),
+            }
+        }
+        for c in children {
+            if let Node::Dir(n, _) = c {
+                lines.push(format!("$ cd {}", n));
+                write_transcript(c, scale, lines);
+                lines.push("$ cd ..".to_string());
+            }
+        }
+    }
+}
+
+fn total(node: &Node) -> u64 {
+    match node {
+        Node::File(_, s) => *s,
+        Node::Dir(_, children) => children.iter().map(total).sum(),
+    }
+}
+
+// A terminal transcript exploring `size` directories. File sizes are scaled so
+// the disk is between 45M and 65M full - part B needs more than 40M in use.
+fn day07(rng: &mut Rng, size: usize) -> String {
+    // Build the tree as a list of directories, each attached to an earlier one.
+    let mut contents: Vec<Vec<Node>> = vec![vec![]];
+    let mut names: Vec<HashSet<String>> = vec![HashSet::new()];
+    let mut parents = vec![];
+    for d in 1..size {
+        let parent = rng.below(d);
+        parents.push((parent, name(rng, &mut names[parent])));
+        contents.push(vec![]);
+        names.push(HashSet::new());
+    }
+    // Empty directories never show up in real transcripts.
+    for d in 0..size {
+        for _ in 0..rng.range(1, 4) {
+            let n = name(rng, &mut names[d]);
+            contents[d].push(Node::File(n, rng.range(1000, 300000) as u64));
+        }
+    }
+    // Fold directories into their parents, deepest (latest) first.
+    for (d, (parent, n)) in parents.into_iter().enumerate().rev() {
+        let children = std::mem::take(&mut contents[d + 1]);
+        contents[parent].push(Node::Dir(n, children));
+    }
+    let mut root_children = std::mem::take(&mut contents[0]);
+    rng.shuffle(&mut root_children);
+    let root = Node::Dir("/".to_string(), root_children);
+
+    let scale = rng.range(45_000_000, 65_000_000) as f64 / total(&root).max(1) as f64;
+    let mut lines = vec!["$ cd /".to_string()];
+    write_transcript(&root, scale, &mut lines);
+    lines.join("\n")
+}
+
+// A `size` by `size` forest of single digit trees.
+fn day08(rng: &mut Rng, size: usize) -> String {
+    (0..size)
+        .map(|_| {
+            (0..size)
+                .map(|_| rng.range(0, 9).to_string())
+                .collect::<String>()
+        })
+        .collect::<Vec<_>>()
+        .join("\n")
+}
+
+// `size` head motions.
+fn day09(rng: &mut Rng, size: usize) -> String {
+    (0..size)
+        .map(|_| format!("{} {}", rng.pick(&['U', 'D', 'L', 'R']), rng.range(1, 19)))
+        .collect::<Vec<_>>()
+        .join("\n")
+}
+
+// `size` instructions, keeping X within the 40 pixel screen most of the time.
+fn day10(rng: &mut Rng, size: usize) -> String {
+    let mut x = 1;
+    (0..size)
+        .map(|_| {
+            if rng.chance(30) {
+                "noop".to_string()
+            } else {
+                let v = rng.range(-x.min(15) - 1, (39 - x).min(15));
+                x += v;
+                format!("addx {}", v)
+            }
+        })
+        .collect::<Vec<_>>()
+        .join("\n")
+}
+
+// Notes for `size` monkeys (at least two), each testing a distinct prime.
+fn day11(rng: &mut Rng, size: usize) -> String {
+    let size = size.max(2);
+    let mut primes = vec![];
+    let mut p = 2;
+    while primes.len() < size {
+        if (2..p).all(|d| p % d != 0) {
+            primes.push(p);
+        }
+        p += 1;
+    }
+    rng.shuffle(&mut primes);
+
+    let mut monkeys = vec![];
+    for (i, prime) in primes.iter().enumerate() {
+        let items = (0..rng.range(1, 8))
+            .map(|_| rng.range(50, 99).to_string())
+            .collect::<Vec<_>>()
+            .join(", ");
+        let operation = match rng.below(3) {
+            0 => "old * old".to_string(),
+            1 => format!("old * {}", rng.range(2, 19)),
+            _ => format!("old + {}", rng.range(1, 8)),
+        };
+        let mut others = (0..size).filter(|&m| m != i).collect::<Vec<_>>();
+        rng.shuffle(&mut others);
+        let if_false = if size > 2 { others[1] } else { others[0] };
+        monkeys.push(format!(
+            "Monkey {}:\n  Starting items: {}\n  Operation: new = {}\n  Test: divisible by {}\n    If true: throw to monkey {}\n    If false: throw to monkey {}",
+            i, items, operation, prime, others[0], if_false
+        ));
+    }
+    monkeys.join("\n\n")
+}
+
+// A heightmap with `size` rows (and at least 30 columns). Heights rise steadily
+// from S in the top left to E in the bottom right, and a random staircase
+// between the two is never lowered, so both parts always have a route.
+fn day12(rng: &mut Rng, size: usize) -> String {
+    let rows = size.max(2);
+    let cols = (rows * 3).max(30);
+    let span = rows + cols - 2;
+
+    let mut staircase = HashSet::new();
+    let (mut r, mut c) = (0, 0);
+    staircase.insert((r, c));
+    while (r, c) != (rows - 1, cols - 1) {
+        if c == cols - 1 || (r < rows - 1 && rng.chance(rows as u64 * 100 / span as u64)) {
+            r += 1;
+        } else {
+            c += 1;
+        }
+        staircase.insert((r, c));
+    }
+
+    let mut grid = vec![];
+    for r in 0..rows {
+        let mut row = String::new();
+        for c in 0..cols {
+            let mut height = (r + c) * 25 / span;
+            if !staircase.contains(&(r, c)) && rng.chance(25) {
+                height -= rng.below(height + 1);
+            }
+            row.push((b'a' + height as u8) as char);
+        }
+        grid.push(row);
+    }
+    grid[0].replace_range(0..1, "S");
+    grid[rows - 1].replace_range(cols - 1..cols, "E");
+    grid.join("\n")
+}
+
+fn packet(rng: &mut Rng, depth: u32) -> String {
+    let items = (0..rng.range(0, 4))
+        .map(|_| {
+            if depth < 4 && rng.chance(30) {
+                packet(rng, depth + 1)
+            } else {
+                rng.range(0, 10).to_string()
+            }
+        })
+        .collect::<Vec<_>>();
+    format!("[{}]", items.join(","))
+}
+
+// `size` pairs of packets.
+fn day13(rng: &mut Rng, size: usize) -> String {
+    (0..size)
+        .map(|_| format!("{}\n{}", packet(rng, 0), packet(rng, 0)))
+        .collect::<Vec<_>>()
+        .join("\n\n")
+}
+
+// `size` rock paths made of horizontal and vertical segments below the source.
+fn day14(rng: &mut Rng, size: usize) -> String {
+    let width = (20 + size as i64 * 2).min(400);
+    let depth = 10 + size as i64 * 2;
+    (0..size)
+        .map(|_| {
+            let (mut x, mut y) = (rng.range(500 - width, 500 + width), rng.range(2, depth));
+            let mut points = vec![format!("{},{}", x, y)];
+            for i in 0..rng.range(1, 5) {
+                if i % 2 == 0 {
+                    x += rng.range(1, 6) * if rng.chance(50) { 1 } else { -1 };
+                } else {
+                    y = (y + rng.range(1, 6) * if rng.chance(50) { 1 } else { -1 }).max(2);
+                }
+                points.push(format!("{},{}", x, y));
+            }
+            points.join(" -> ")
+        })
+        .collect::<Vec<_>>()
+        .join("\n")
+}
+
+// `size` sensors, each reporting a beacon that is strictly its closest. The
+// area scales with `size` so small inputs stay small.
+fn day15(rng: &mut Rng, size: usize) -> String {
+    let span = size as i64 * 1000;
+    let beacons = (0..size / 2 + 1)
+        .map(|_| (rng.range(0, span), rng.range(0, span)))
+        .collect::<Vec<_>>();
+
+    let mut sensors = vec![];
+    let mut taken = beacons.iter().cloned().collect::<HashSet<_>>();
+    while sensors.len() < size {
+        let s = (rng.range(0, span), rng.range(0, span));
+        let mut distances = beacons
+            .iter()
+            .map(|b| ((s.0 - b.0).abs() + (s.1 - b.1).abs(), *b))
+            .collect::<Vec<_>>();
+        distances.sort();
+        // Ties would make the "closest beacon" ambiguous.
+        if distances.len() > 1 && distances[0].0 == distances[1].0 {
+            continue;
+        }
+        if taken.insert(s) {
+            sensors.push((s, distances[0].1));
+        }
+    }
+    sensors
+        .iter()
+        .map(|(s, b)| {
+            format!(
+                "Sensor at x={}, y={}: closest beacon is at x={}, y={}",
+                s.0, s.1, b.0, b.1
+            )
+        })
+        .collect::<Vec<_>>()
+        .join("\n")
+}
+
+// A connected graph of `size` valves (at least two), starting from AA.
+fn day16(rng: &mut Rng, size: usize) -> String {
+    let size = size.clamp(2, 26 * 26);
+    let mut names = vec!["AA".to_string()];
+    let mut taken = HashSet::new();
+    taken.insert("AA".to_string());
+    while names.len() < size {
+        let n = format!(
+            "{}{}",
+            item(rng.range(27, 52) as usize),
+            item(rng.range(27, 52) as usize)
+        );
+        if taken.insert(n.clone()) {
+            names.push(n);
+        }
+    }
+
+    // A random spanning tree, plus a few shortcuts.
+    let mut edges = BTreeSet::new();
+    for v in 1..size {
+        edges.insert((rng.below(v), v));
+    }
+    for _ in 0..size / 3 {
+        let (a, b) = (rng.below(size), rng.below(size));
+        if a != b {
+            edges.insert((a.min(b), a.max(b)));
+        }
+    }
+
+    let mut lines = vec![];
+    for (v, n) in names.iter().enumerate() {
+        let neighbours = edges
+            .iter()
+            .filter_map(|&(a, b)| match (a == v, b == v) {
+                (true, _) => Some(names[b].as_str()),
+                (_, true) => Some(names[a].as_str()),
+                _ => None,
+            })
+            .collect::<Vec<_>>();
+        let flow = if v > 0 && rng.chance(35) {
+            rng.range(1, 25)
+        } else {
+            0
+        };
+        let tunnels = if neighbours.len() == 1 {
+            "tunnel leads to valve"
+        } else {
+            "tunnels lead to valves"
+        };
+        lines.push(format!(
+            "Valve {} has flow rate={}; {} {}",
+            n,
+            flow,
+            tunnels,
+            neighbours.join(", ")
+        ));
+    }
+    rng.shuffle(&mut lines);
+    lines.join("\n")
+}
+
+// `size` jets of gas.
+fn day17(rng: &mut Rng, size: usize) -> String {
+    (0..size).map(|_| *rng.pick(&['<', '>'])).collect()
+}
+
+// `size` distinct cubes packed into a box just big enough to hold them. Nothing
+// touches the zero planes, so the outside is always reachable from the origin.
+fn day18(rng: &mut Rng, size: usize) -> String {
+    let side = ((size as f64 * 3.0).cbrt().ceil() as i64).max(2);
+    let mut cubes = HashSet::new();
+    let mut lines = vec![];
+    while lines.len() < size.min((side * side * side) as usize) {
+        let c = (rng.range(1, side), rng.range(1, side), rng.range(1, side));
+        if cubes.insert(c) {
+            lines.push(format!("{},{},{}", c.0, c.1, c.2));
+        }
+    }
+    lines.join("\n")
+}
+
+// `size` blueprints with costs in the same ranges as the real puzzle.
+fn day19(rng: &mut Rng, size: usize) -> String {
+    (1..=size)
+        .map(|i| {
+            format!(
+                "Blueprint {}: Each ore robot costs {} ore. Each clay robot costs {} ore. Each obsidian robot costs {} ore and {} clay. Each geode robot costs {} ore and {} obsidian.",
+                i,
+                rng.range(2, 4),
+                rng.range(2, 4),
+                rng.range(2, 4),
+                rng.range(5, 20),
+                rng.range(2, 4),
+                rng.range(5, 20)
+            )
+        })
+        .collect::<Vec<_>>()
+        .join("\n")
+}
+
+// `size` numbers (at least two) containing exactly one zero.
+fn day20(rng: &mut Rng, size: usize) -> String {
+    let size = size.max(2);
+    let zero = rng.below(size);
+    (0..size)
+        .map(|i| {
+            if i == zero {
+                0
+            } else {
+                let v = rng.range(1, 10000);
+                if rng.chance(50) {
+                    v
+                } else {
+                    -v
+                }
+            }
+        })
+        .map(|v| v.to_string())
+        .collect::<Vec<_>>()
+        .join("\n")
+}
+
+fn name4(rng: &mut Rng, taken: &mut HashSet<String>) -> String {
+    loop {
+        let n = (0..4)
+            .map(|_| item(rng.range(1, 26) as usize))
+            .collect::<String>();
+        if n != "root" && n != "humn" && taken.insert(n.clone()) {
+            return n;
+        }
+    }
+}
+
+// Builds a monkey that shouts `value`, with about `budget` operations beneath it.
+fn shout(
+    rng: &mut Rng,
+    value: i64,
+    budget: usize,
+    humn_path: bool,
+    names: &mut HashSet<String>,
+    lines: &mut Vec<String>,
+) -> String {
+    if budget == 0 {
+        if humn_path {
+            // humn's own number is irrelevant to part B - that's the whole point.
+            lines.push(format!("humn: {}", rng.range(1, 5000)));
+            return "humn".to_string();
+        }
+        let n = name4(rng, names);
+        lines.push(format!("{}: {}", n, value));
+        return n;
+    }
+    let n = name4(rng, names);
+
+    // Work backwards from the value this monkey must produce. Multiplication is
+    // only used when it divides cleanly, so inverting it is exact.
+    let divisor = (2..10).find(|d| value % d == 0);
+    let r = rng.range(1, 20);
+    let (l_val, op, r_val) = match (rng.below(4), divisor) {
+        (0, _) if value > 1 => (value - r.min(value - 1), '+', r.min(value - 1)),
+        (1, Some(d)) => (value / d, '*', d),
+        (2, _) => (value * r, '/', r),
+        _ => (value + r, '-', r),
+    };
+
+    // Exactly one side continues towards humn, and never as a divisor - part A
+    // would risk dividing by zero.
+    let (l_budget, r_budget, humn_left) = if humn_path {
+        let side = rng.below(4);
+        if op == '/' || rng.chance(50) {
+            (budget - 1, side, true)
+        } else {
+            (side, budget - 1, false)
+        }
+    } else {
+        let split = rng.below(budget);
+        (split, budget - 1 - split, false)
+    };
+    let l = shout(rng, l_val, l_budget, humn_path && humn_left, names, lines);
+    let r = shout(rng, r_val, r_budget, humn_path && !humn_left, names, lines);
+    lines.push(format!("{}: {} {} {}", n, l, op, r));
+    n
+}
+
+// Roughly `size` monkeys. The humn side is built to equal the other side, so
+// part B always has an integer answer.
+fn day21(rng: &mut Rng, size: usize) -> String {
+    let mut names = HashSet::new();
+    let mut lines = vec![];
+    let depth = (size / 8).clamp(1, 100);
+    let target = rng.range(100, 100000);
+    let humn = shout(rng, target, depth, true, &mut names, &mut lines);
+    let other_budget = size.saturating_sub(depth * 3).max(1);
+    let other = shout(rng, target, other_budget, false, &mut names, &mut lines);
+    lines.push(format!("root: {} + {}", humn, other));
+    rng.shuffle(&mut lines);
+    lines.join("\n")
+}
+
+// A board folded the same way as the real input, which is the only one day22
+// knows how to fold, with a path of `size` * 20 turns.
+fn day22(rng: &mut Rng, size: usize) -> String {
+    let face = day22::FACE_SIZE;
+    let mut rows = vec![];
+    for y in 0..4 * face {
+        let mut row = String::new();
+        for x in 0..3 * face {
+            if !day22::NET.contains(&(x / face, y / face)) {
+                row.push(' ');
+            } else if (x, y) != (face, 0) && rng.chance(10) {
+                // The starting square is always left open.
+                row.push('#');
+            } else {
+                row.push('.');
+            }
+        }
+        rows.push(row.trim_end().to_string());
+    }
+
+    let mut path = rng.range(1, 50).to_string();
+    for _ in 0..size * 20 {
+        path.push(*rng.pick(&['L', 'R']));
+        path.push_str(&rng.range(1, 50).to_string());
+    }
+    format!("{}\n\n{}", rows.join("\n"), path)
+}
+
+// A `size` by `size` grove, roughly half full of elves.
+fn day23(rng: &mut Rng, size: usize) -> String {
+    (0..size)
+        .map(|_| {
+            (0..size)
+                .map(|_| if rng.chance(50) { '#' } else { '.' })
+                .collect::<String>()
+        })
+        .collect::<Vec<_>>()
+        .join("\n")
+}
+
+// A valley `size` rows high and four times as wide. Vertical blizzards are kept
+// out of the entry and exit columns, as they would otherwise leave the valley.
+fn day24(rng: &mut Rng, size: usize) -> String {
+    let (height, width) = (size.max(1) + 2, size.max(1) * 4 + 2);
+    let mut rows = vec![];
+    for y in 0..height {
+        let mut row = String::new();
+        for x in 0..width {
+            let c = if (x, y) == (1, 0) || (x, y) == (width - 2, height - 1) {
+                '.'
+            } else if y == 0 || y == height - 1 || x == 0 || x == width - 1 {
+                '#'
+            } else if rng.chance(40) {
+                if x == 1 || x == width - 2 {
+                    *rng.pick(&['<', '>'])
+                } else {
+                    *rng.pick(&['<', '>', '^', 'v'])
+                }
+            } else {
+                '.'
+            };
+            row.push(c);
+        }
+        rows.push(row);
+    }
+    rows.join("\n")
+}
+
+fn snafu(mut n: i64) -> String {
+    let mut digits = vec![];
+    while n > 0 {
+        let (d, carry) = match n % 5 {
+            3 => ('=', 1),
+            4 => ('-', 1),
+            r => ((b'0' + r as u8) as char, 0),
+        };
+        digits.push(d);
+        n = n / 5 + carry;
+    }
+    digits.iter().rev().collect()
+}
+
+// `size` fuel requirements in SNAFU.
+fn day25(rng: &mut Rng, size: usize) -> String {
+    (0..size)
+        .map(|_| {
+            let digits = rng.range(1, 12) as u32;
+            snafu(rng.range(1, 10_i64.pow(digits)))
+        })
+        .collect::<Vec<_>>()
+        .join("\n")
+}
+
+#[test]
+fn same_seed_same_input() {
+    for day in 1..=25 {
+        assert_eq!(generate(day, 7, 12), generate(day, 7, 12));
+    }
+    assert_ne!(generate(15, 1, 12), generate(15, 2, 12));
+    assert_eq!(None, generate(26, 1, 12));
+}
+
+#[test]
+fn rucksack_groups_share_one_badge() {
+    let input = generate(3, 3, 30).unwrap();
+    let rucksacks = input.lines().collect::<Vec<_>>();
+    for group in rucksacks.chunks(3) {
+        let common = group[0]
+            .chars()
+            .filter(|c| group[1].contains(*c) && group[2].contains(*c))
+            .collect::<HashSet<_>>();
+        assert_eq!(1, common.len());
+    }
+    for r in rucksacks {
+        let (a, b) = r.split_at(r.len() / 2);
+        assert_eq!(
+            1,
+            a.chars()
+                .filter(|c| b.contains(*c))
+                .collect::<HashSet<_>>()
+                .len()
+        );
+    }
+}
+
+#[test]
+fn snafu_numbers() {
+    assert_eq!("1=-0-2", snafu(1747));
+    assert_eq!("2=-01", snafu(976));
+    assert_eq!("1=11-2", snafu(2022));
+}
+
+#[test]
+fn boards_fold_like_the_real_input() {
+    for size in [1, 10] {
+        let board = generate(22, 3, size).unwrap();
+        assert!(crate::validate::validate(22, &board).unwrap().is_empty());
+    }
+}
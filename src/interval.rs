@@ -0,0 +1,258 @@
+use std::{iter::FromIterator, ops::RangeInclusive};
+
+// A set of integers, kept as sorted inclusive ranges that neither overlap nor
+// touch - so there's only ever one way to store a given set.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct IntervalSet {
+    ranges: Vec<(i64, i64)>,
+}
+
+impl IntervalSet {
+    pub fn new() -> IntervalSet {
+        IntervalSet::default()
+    }
+
+    // Adds every integer in `range`, merging it with any ranges it overlaps or
+    // touches. Empty ranges are ignored.
+    pub fn insert(&mut self, range: RangeInclusive<i64>) {
+        let (mut lo, mut hi) = range.into_inner();
+        if lo > hi {
+            return;
+        }
+        let start = self
+            .ranges
+            .partition_point(|&(_, h)| h.saturating_add(1) < lo);
+        let end = self
+            .ranges
+            .partition_point(|&(l, _)| l <= hi.saturating_add(1));
+        if start < end {
+            lo = lo.min(self.ranges[start].0);
+            hi = hi.max(self.ranges[end - 1].1);
+        }
+        self.ranges.splice(start..end, [(lo, hi)]);
+    }
+
+    pub fn union(&self, other: &IntervalSet) -> IntervalSet {
+        let mut union = self.clone();
+        for range in other.ranges() {
+            union.insert(range);
+        }
+        union
+    }
+
+    pub fn intersection(&self, other: &IntervalSet) -> IntervalSet {
+        let (mut i, mut j) = (0, 0);
+        let mut ranges = vec![];
+        while i < self.ranges.len() && j < other.ranges.len() {
+            let (a, b) = (self.ranges[i], other.ranges[j]);
+            let (lo, hi) = (a.0.max(b.0), a.1.min(b.1));
+            if lo <= hi {
+                ranges.push((lo, hi));
+            }
+            // Whichever ends first can't meet anything further along.
+            if a.1 < b.1 {
+                i += 1;
+            } else {
+                j += 1;
+            }
+        }
+        IntervalSet { ranges }
+    }
+
+    pub fn difference(&self, other: &IntervalSet) -> IntervalSet {
+        let mut ranges = vec![];
+        let mut j = 0;
+        for &(lo, hi) in &self.ranges {
+            while j < other.ranges.len() && other.ranges[j].1 < lo {
+                j += 1;
+            }
+            // What's left of this range to the right of everything removed so far.
+            let mut rest = Some(lo);
+            for &(cut_lo, cut_hi) in other.ranges[j..].iter().take_while(|r| r.0 <= hi) {
+                let Some(lo) = rest else { break };
+                if cut_lo > lo {
+                    ranges.push((lo, cut_lo - 1));
+                }
+                rest = if cut_hi < hi { Some(cut_hi + 1) } else { None };
+            }
+            if let Some(lo) = rest {
+                ranges.push((lo, hi));
+            }
+        }
+        IntervalSet { ranges }
+    }
+
+    pub fn contains(&self, x: i64) -> bool {
+        let i = self.ranges.partition_point(|&(_, h)| h < x);
+        self.ranges.get(i).is_some_and(|&(l, _)| l <= x)
+    }
+
+    pub fn is_superset(&self, other: &IntervalSet) -> bool {
+        other.difference(self).is_empty()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.ranges.is_empty()
+    }
+
+    // How many integers are in the set.
+    pub fn len(&self) -> u64 {
+        self.ranges.iter().map(|&(l, h)| h.abs_diff(l) + 1).sum()
+    }
+
+    pub fn ranges(&self) -> impl Iterator<Item = RangeInclusive<i64>> + '_ {
+        self.ranges.iter().map(|&(l, h)| l..=h)
+    }
+
+    // The holes between the first and last integers in the set.
+    pub fn gaps(&self) -> impl Iterator<Item = RangeInclusive<i64>> + '_ {
+        self.ranges.windows(2).map(|w| w[0].1 + 1..=w[1].0 - 1)
+    }
+}
+
+impl From<RangeInclusive<i64>> for IntervalSet {
+    fn from(range: RangeInclusive<i64>) -> IntervalSet {
+        let mut set = IntervalSet::new();
+        set.insert(range);
+        set
+    }
+}
+
+impl FromIterator<RangeInclusive<i64>> for IntervalSet {
+    fn from_iter<I: IntoIterator<Item = RangeInclusive<i64>>>(iter: I) -> IntervalSet {
+        let mut set = IntervalSet::new();
+        for range in iter {
+            set.insert(range);
+        }
+        set
+    }
+}
+
+#[test]
+fn merges_touching_ranges() {
+    let mut set = IntervalSet::from_iter([1..=3, 7..=9]);
+    // Backwards ranges are empty.
+    let (lo, hi) = (6, 5);
+    set.insert(lo..=hi);
+    assert_eq!(vec![1..=3, 7..=9], set.ranges().collect::<Vec<_>>());
+    assert_eq!(vec![4..=6], set.gaps().collect::<Vec<_>>());
+    set.insert(4..=6);
+    assert_eq!(vec![1..=9], set.ranges().collect::<Vec<_>>());
+    assert_eq!(9, set.len());
+
+    let edge = IntervalSet::from_iter([i64::MIN..=0, 1..=i64::MAX]);
+    assert_eq!(vec![i64::MIN..=i64::MAX], edge.ranges().collect::<Vec<_>>());
+    assert_eq!(
+        vec![i64::MIN..=-1, 1..=i64::MAX],
+        edge.difference(&IntervalSet::from(0..=0))
+            .ranges()
+            .collect::<Vec<_>>()
+    );
+}
+
+// Every operation, checked against a bitmap of a small stretch of integers.
+#[test]
+fn matches_bitmap() {
+    const LO: i64 = -20;
+    const HI: i64 = 20;
+    type Ranges = Vec<(i64, i64)>;
+
+    // Runs of set bits, as the ranges an IntervalSet should hold.
+    fn runs(bits: &[bool]) -> Ranges {
+        let mut runs: Ranges = vec![];
+        for (x, _) in (LO..=HI).zip(bits).filter(|(_, &b)| b) {
+            match runs.last_mut() {
+                Some(run) if run.1 + 1 == x => run.1 = x,
+                _ => runs.push((x, x)),
+            }
+        }
+        runs
+    }
+
+    fn bitmap(ranges: &Ranges) -> Vec<bool> {
+        (LO..=HI)
+            .map(|x| ranges.iter().any(|&(l, h)| l <= x && x <= h))
+            .collect()
+    }
+
+    fn set(ranges: &Ranges) -> IntervalSet {
+        ranges.iter().map(|&(l, h)| l..=h).collect()
+    }
+
+    fn inner(set: &IntervalSet) -> Ranges {
+        set.ranges().map(|r| r.into_inner()).collect()
+    }
+
+    crate::differential::check(
+        500,
+        |rng| {
+            let mut ranges = || {
+                (0..rng.range(0, 6))
+                    .map(|_| {
+                        let lo = rng.range(LO, HI);
+                        (lo, lo + rng.range(-2, 8))
+                    })
+                    .map(|(lo, hi)| (lo, hi.min(HI)))
+                    .collect::<Ranges>()
+            };
+            (ranges(), ranges())
+        },
+        |(a, b): &(Ranges, Ranges)| {
+            let mut smaller = vec![];
+            for i in 0..a.len() {
+                let mut a = a.clone();
+                a.remove(i);
+                smaller.push((a, b.clone()));
+            }
+            for i in 0..b.len() {
+                let mut b = b.clone();
+                b.remove(i);
+                smaller.push((a.clone(), b));
+            }
+            smaller
+        },
+        |(a, b)| {
+            let (a, b) = (bitmap(a), bitmap(b));
+            let zip = |f: fn(bool, bool) -> bool| {
+                runs(&a.iter().zip(&b).map(|(&x, &y)| f(x, y)).collect::<Vec<_>>())
+            };
+            let a_runs = runs(&a);
+            (
+                vec![
+                    a_runs.clone(),
+                    runs(&b),
+                    zip(|x, y| x || y),
+                    zip(|x, y| x && y),
+                    zip(|x, y| x && !y),
+                ],
+                a.iter().filter(|&&x| x).count() as u64,
+                (LO - 1..=HI + 1)
+                    .filter(|&x| (LO..=HI).contains(&x) && a[(x - LO) as usize])
+                    .collect::<Vec<_>>(),
+                a.iter().zip(&b).all(|(&x, &y)| x || !y),
+                a_runs
+                    .windows(2)
+                    .map(|w| (w[0].1 + 1, w[1].0 - 1))
+                    .collect::<Ranges>(),
+            )
+        },
+        |(a, b)| {
+            let (a, b) = (set(a), set(b));
+            (
+                vec![
+                    inner(&a),
+                    inner(&b),
+                    inner(&a.union(&b)),
+                    inner(&a.intersection(&b)),
+                    inner(&a.difference(&b)),
+                ],
+                a.len(),
+                (LO - 1..=HI + 1)
+                    .filter(|&x| a.contains(x))
+                    .collect::<Vec<_>>(),
+                a.is_superset(&b),
+                a.gaps().map(|r| r.into_inner()).collect::<Ranges>(),
+            )
+        },
+    );
+}
@@ -0,0 +1,230 @@
+use std::{collections::BTreeMap, fs, io, path::Path};
+
+use serde::Deserialize;
+
+// The parts of a private leaderboard's JSON export that we use.
+#[derive(Deserialize)]
+struct Leaderboard {
+    event: String,
+    members: BTreeMap<String, Member>,
+}
+
+#[derive(Deserialize)]
+struct Member {
+    id: u64,
+    name: Option<String>,
+    local_score: u64,
+    stars: u32,
+    // Day -> part -> when the star was earned.
+    completion_day_level: BTreeMap<u8, BTreeMap<u8, Star>>,
+}
+
+#[derive(Deserialize)]
+struct Star {
+    get_star_ts: i64,
+}
+
+impl Member {
+    fn display_name(&self) -> String {
+        match &self.name {
+            Some(name) => name.clone(),
+            None => format!("(anonymous user #{})", self.id),
+        }
+    }
+
+    fn star(&self, day: u8, part: u8) -> Option<i64> {
+        self.completion_day_level
+            .get(&day)
+            .and_then(|parts| parts.get(&part))
+            .map(|s| s.get_star_ts)
+    }
+}
+
+// Puzzles unlock at midnight EST (UTC-5) each day in December.
+fn unlock_time(year: i64, day: u8) -> i64 {
+    // Days from 1970-01-01 to December 1st of `year` - Howard Hinnant's
+    // days_from_civil, specialised to a month after February.
+    let era = year.div_euclid(400);
+    let year_of_era = year - era * 400;
+    let day_of_year = (153 * (12 - 3) + 2) / 5;
+    let day_of_era = year_of_era * 365 + year_of_era / 4 - year_of_era / 100 + day_of_year;
+    let days = era * 146097 + day_of_era - 719468;
+    (days + day as i64 - 1) * 86400 + 5 * 3600
+}
+
+fn format_duration(seconds: i64) -> String {
+    format!(
+        "{}:{:02}:{:02}",
+        seconds / 3600,
+        seconds / 60 % 60,
+        seconds % 60
+    )
+}
+
+fn rankings(board: &Leaderboard) -> Vec<&Member> {
+    let mut members = board.members.values().collect::<Vec<_>>();
+    members.sort_by(|a, b| {
+        b.local_score
+            .cmp(&a.local_score)
+            .then(b.stars.cmp(&a.stars))
+            .then(a.display_name().cmp(&b.display_name()))
+    });
+    members
+}
+
+// Everyone with both stars for a day, and how long part 2 took them after part 1.
+fn part_2_deltas(board: &Leaderboard, day: u8) -> Vec<(&Member, i64)> {
+    let mut deltas = board
+        .members
+        .values()
+        .filter_map(|m| Some((m, m.star(day, 2)? - m.star(day, 1)?)))
+        .collect::<Vec<_>>();
+    deltas.sort_by_key(|&(m, delta)| (delta, m.id));
+    deltas
+}
+
+// Whoever earned both stars soonest after the puzzle unlocked.
+fn fastest(board: &Leaderboard, day: u8) -> Option<(&Member, i64)> {
+    let year = board.event.parse().unwrap_or(2022);
+    board
+        .members
+        .values()
+        .filter_map(|m| Some((m, m.star(day, 2)? - unlock_time(year, day))))
+        .min_by_key(|&(m, time)| (time, m.id))
+}
+
+fn days(board: &Leaderboard) -> Vec<u8> {
+    let mut days = board
+        .members
+        .values()
+        .flat_map(|m| m.completion_day_level.keys().cloned())
+        .collect::<Vec<_>>();
+    days.sort_unstable();
+    days.dedup();
+    days
+}
+
+fn csv_field(s: &str) -> String {
+    if s.contains([',', '"', '\n']) {
+        format!("\"{}\"", s.replace('"', "\"\""))
+    } else {
+        s.to_string()
+    }
+}
+
+// One row per member per day they earned a star on, times in seconds.
+fn print_csv(board: &Leaderboard) {
+    let year = board.event.parse().unwrap_or(2022);
+    println!("member,day,part_1_time,part_2_time,part_2_delta");
+    for m in rankings(board) {
+        for &day in m.completion_day_level.keys() {
+            let unlock = unlock_time(year, day);
+            let (part_1, part_2) = (m.star(day, 1), m.star(day, 2));
+            let time = |ts: Option<i64>| ts.map_or(String::new(), |ts| (ts - unlock).to_string());
+            let delta = match (part_1, part_2) {
+                (Some(a), Some(b)) => (b - a).to_string(),
+                _ => String::new(),
+            };
+            println!(
+                "{},{},{},{},{}",
+                csv_field(&m.display_name()),
+                day,
+                time(part_1),
+                time(part_2),
+                delta
+            );
+        }
+    }
+}
+
+fn print_tables(board: &Leaderboard) {
+    println!("Rankings");
+    for (i, m) in rankings(board).iter().enumerate() {
+        println!(
+            "{:>3}. {:<30} {:>5} points {:>3} stars",
+            i + 1,
+            m.display_name(),
+            m.local_score,
+            m.stars
+        );
+    }
+
+    for day in days(board) {
+        println!("\nDay {}", day);
+        match fastest(board, day) {
+            Some((m, time)) => println!(
+                "  Fastest: {} in {}",
+                m.display_name(),
+                format_duration(time)
+            ),
+            None => println!("  Nobody has both stars yet"),
+        }
+        for (m, delta) in part_2_deltas(board, day) {
+            println!(
+                "  {:<30} part 2 took {}",
+                m.display_name(),
+                format_duration(delta)
+            );
+        }
+    }
+}
+
+fn parse(contents: &str) -> io::Result<Leaderboard> {
+    serde_json::from_str(contents).map_err(|e| io::Error::new(io::ErrorKind::InvalidData, e))
+}
+
+pub fn leaderboard(file: &Path, csv: bool) -> io::Result<()> {
+    let board = parse(&fs::read_to_string(file)?)?;
+    if csv {
+        print_csv(&board);
+    } else {
+        print_tables(&board);
+    }
+    Ok(())
+}
+
+#[test]
+fn rankings_and_fastest() {
+    let input = r#"{
+  "event": "2022",
+  "owner_id": 1,
+  "members": {
+    "1": {"id": 1, "name": "Alice", "stars": 4, "local_score": 8, "global_score": 0,
+          "last_star_ts": 1669958000,
+          "completion_day_level": {
+            "1": {"1": {"get_star_ts": 1669871100, "star_index": 1},
+                  "2": {"get_star_ts": 1669871400, "star_index": 2}},
+            "2": {"1": {"get_star_ts": 1669957500, "star_index": 3},
+                  "2": {"get_star_ts": 1669958000, "star_index": 4}}}},
+    "2": {"id": 2, "name": null, "stars": 3, "local_score": 6, "global_score": 0,
+          "last_star_ts": 1669957300,
+          "completion_day_level": {
+            "1": {"1": {"get_star_ts": 1669871000, "star_index": 1},
+                  "2": {"get_star_ts": 1669871500, "star_index": 2}},
+            "2": {"1": {"get_star_ts": 1669957300, "star_index": 3}}}}
+  }
+}"#;
+    let board = parse(input).unwrap();
+    let names = rankings(&board)
+        .iter()
+        .map(|m| m.display_name())
+        .collect::<Vec<_>>();
+    assert_eq!(vec!["Alice", "(anonymous user #2)"], names);
+
+    let (m, time) = fastest(&board, 1).unwrap();
+    assert_eq!(("Alice", 600), (m.display_name().as_str(), time));
+    assert_eq!("0:10:00", format_duration(time));
+
+    let deltas = part_2_deltas(&board, 1)
+        .iter()
+        .map(|(m, d)| (m.id, *d))
+        .collect::<Vec<_>>();
+    assert_eq!(vec![(1, 300), (2, 500)], deltas);
+    assert!(part_2_deltas(&board, 2).iter().all(|(m, _)| m.id == 1));
+}
+
+#[test]
+fn unlocks_at_midnight_est() {
+    assert_eq!(1669870800, unlock_time(2022, 1));
+    assert_eq!(1701406800, unlock_time(2023, 1));
+}
@@ -1,4 +1,14 @@
+use std::path::{Path, PathBuf};
+
 use structopt::StructOpt;
+
+use context::Context;
+#[cfg(feature = "alloc-stats")]
+use context::Part;
+#[cfg(feature = "alloc-stats")]
+mod alloc_stats;
+mod bench;
+mod context;
 mod day01;
 mod day02;
 mod day03;
@@ -24,6 +34,16 @@ mod day22;
 mod day23;
 mod day24;
 mod day25;
+#[cfg(test)]
+mod differential;
+mod generate;
+mod interval;
+mod leaderboard;
+mod repl;
+mod report;
+mod runner;
+mod validate;
+mod watch;
 
 // #[macro_use]
 // extern crate lazy_static;
@@ -32,37 +52,266 @@ mod day25;
 
 #[derive(StructOpt)]
 struct Cli {
-    day: u8,
+    day: Option<u8>,
+    /// Solves this file instead of the day's bundled input.
+    #[structopt(long, parse(from_os_str))]
+    input: Option<PathBuf>,
+    /// Day 1: how many of the largest groups to add up for part B.
+    #[structopt(long)]
+    top: Option<usize>,
+    /// Day 2: the game's rules, in the format of inputs/day02-rules.txt.
+    #[structopt(long)]
+    rules: Option<String>,
+    /// Day 2: scores the guide under every possible meaning of its second column.
+    #[structopt(long)]
+    analyse: bool,
+    /// Day 4: reports which sections are covered by whom.
+    #[structopt(long)]
+    coverage: bool,
+    /// Day 5: also rearranges with a crane lifting at most this many crates.
+    #[structopt(long)]
+    capacity: Option<usize>,
+    /// Day 5: reports after which move this crate first reaches the top.
+    #[structopt(long)]
+    find_crate: Option<char>,
+    /// Day 5: prints the stacks after this move, or after "all" of them.
+    #[structopt(long)]
+    show_move: Option<String>,
+    /// Day 7: the size of the disk.
+    #[structopt(long)]
+    disk_size: Option<u64>,
+    /// Day 7: how much of the disk has to be free.
+    #[structopt(long)]
+    required_space: Option<u64>,
+    /// Day 7: plans which directories to delete, freeing the fewest "bytes" or
+    /// deleting the fewest "dirs".
+    #[structopt(long)]
+    plan: Option<String>,
+    #[structopt(subcommand)]
+    cmd: Option<Command>,
+}
+
+#[derive(StructOpt)]
+enum Command {
+    /// Re-runs a day whenever its source, input or examples change.
+    Watch { day: u8 },
+    /// Prints a randomly generated input for a day.
+    Gen {
+        day: u8,
+        #[structopt(long, default_value = "1")]
+        seed: u64,
+        #[structopt(long, default_value = "10")]
+        size: usize,
+    },
+    /// Writes an HTML report of answers, timings and output for each day.
+    Report {
+        #[structopt(long, parse(from_os_str))]
+        out: PathBuf,
+        #[structopt(long, default_value = "3")]
+        runs: usize,
+        /// Comma separated days to include - all of them if left out.
+        #[structopt(long, use_delimiter = true)]
+        days: Vec<u8>,
+        /// Expected answers, one "<day> <part> <answer>" per line.
+        #[structopt(long, parse(from_os_str), default_value = "answers.txt")]
+        answers: PathBuf,
+    },
+    /// Times days and appends the results to the benchmark history.
+    Bench {
+        #[structopt(long, default_value = "5")]
+        runs: usize,
+        /// Comma separated days to time - all of them if left out.
+        #[structopt(long, use_delimiter = true)]
+        days: Vec<u8>,
+        /// Shows the recorded history for a day instead of timing anything.
+        #[structopt(long)]
+        history: Option<u8>,
+    },
+    /// Runs days interactively against pasted snippets of input.
+    Repl,
+    /// Checks a day's input against the assumptions its solver makes.
+    Check {
+        day: u8,
+        /// Checks this file instead of the bundled input.
+        #[structopt(long, parse(from_os_str))]
+        input: Option<PathBuf>,
+    },
+    /// Summarises a saved private leaderboard JSON export.
+    Leaderboard {
+        #[structopt(parse(from_os_str))]
+        file: PathBuf,
+        /// Prints one row per member per day instead of tables.
+        #[structopt(long)]
+        csv: bool,
+    },
+    /// Explores the filesystem a day 7 terminal transcript describes.
+    Fs {
+        /// Reads this transcript instead of the bundled input.
+        #[structopt(long, parse(from_os_str))]
+        input: Option<PathBuf>,
+        #[structopt(subcommand)]
+        listing: day07::Listing,
+    },
+}
+
+// Days that only do a part's work when `ctx.wants` it. The rest solve both
+// parts together, so their allocations are only measured for the whole day.
+#[cfg(feature = "alloc-stats")]
+const SEPARATE_PARTS: [u8; 13] = [2, 3, 5, 8, 9, 11, 12, 13, 14, 15, 16, 19, 21];
+
+fn run_day(day: u8, ctx: &Context) {
+    match day {
+        1 => day01::day01(ctx),
+        2 => day02::day02(ctx),
+        3 => day03::day03(ctx),
+        4 => day04::day04(ctx),
+        5 => day05::day05(ctx),
+        6 => day06::day06(ctx),
+        7 => day07::day07(ctx),
+        8 => day08::day08(ctx),
+        9 => day09::day09(ctx),
+        10 => day10::day10(ctx),
+        11 => day11::day11(ctx),
+        12 => day12::day12(ctx),
+        13 => day13::day13(ctx),
+        14 => day14::day14(ctx),
+        15 => day15::day15(ctx),
+        16 => day16::day16(ctx),
+        17 => day17::day17(ctx),
+        18 => day18::day18(ctx),
+        19 => day19::day19(ctx),
+        20 => day20::day20(ctx),
+        21 => day21::day21(ctx),
+        22 => day22::day22(ctx),
+        23 => day23::day23(ctx),
+        24 => day24::day24(ctx),
+        25 => day25::day25(ctx),
+        _ => println!("Unimplemented day: {}", day),
+    }
 }
 
 fn main() {
     let args = Cli::from_args();
-    match args.day {
-        1 => day01::day01(),
-        2 => day02::day02(),
-        3 => day03::day03(),
-        4 => day04::day04(),
-        5 => day05::day05(),
-        6 => day06::day06(),
-        7 => day07::day07(),
-        8 => day08::day08(),
-        9 => day09::day09(),
-        10 => day10::day10(),
-        11 => day11::day11(),
-        12 => day12::day12(),
-        13 => day13::day13(),
-        14 => day14::day14(),
-        15 => day15::day15(),
-        16 => day16::day16(),
-        17 => day17::day17(),
-        18 => day18::day18(),
-        19 => day19::day19(),
-        20 => day20::day20(),
-        21 => day21::day21(),
-        22 => day22::day22(),
-        23 => day23::day23(),
-        24 => day24::day24(),
-        25 => day25::day25(),
-        _ => println!("Unimplemented day: {}", args.day),
+    match (args.cmd, args.day) {
+        (Some(Command::Watch { day }), _) => watch::watch(day),
+        (Some(Command::Gen { day, seed, size }), _) => match generate::generate(day, seed, size) {
+            Some(input) => print!("{}", input),
+            None => println!("No generator for day: {}", day),
+        },
+        (
+            Some(Command::Report {
+                out,
+                runs,
+                days,
+                answers,
+            }),
+            _,
+        ) => {
+            let days = if days.is_empty() {
+                (1..=25).collect()
+            } else {
+                days
+            };
+            if let Err(e) = report::report(&out, &days, runs, &answers) {
+                eprintln!("{}: {}", out.display(), e);
+                std::process::exit(1);
+            }
+        }
+        (
+            Some(Command::Bench {
+                history: Some(day), ..
+            }),
+            _,
+        ) => {
+            if let Err(e) = bench::show_history(day, Path::new(bench::HISTORY_FILE)) {
+                eprintln!("{}: {}", bench::HISTORY_FILE, e);
+                std::process::exit(1);
+            }
+        }
+        (Some(Command::Bench { runs, days, .. }), _) => {
+            let days = if days.is_empty() {
+                (1..=25).collect()
+            } else {
+                days
+            };
+            if let Err(e) = bench::bench(&days, runs, Path::new(bench::HISTORY_FILE)) {
+                eprintln!("{}: {}", bench::HISTORY_FILE, e);
+                std::process::exit(1);
+            }
+        }
+        (Some(Command::Repl), _) => repl::repl(),
+        (Some(Command::Check { day, input }), _) => match validate::check(day, input.as_deref()) {
+            Ok(true) => {}
+            Ok(false) => std::process::exit(1),
+            Err(e) => {
+                let path = input.unwrap_or_default();
+                eprintln!("{}: {}", path.display(), e);
+                std::process::exit(1);
+            }
+        },
+        (Some(Command::Leaderboard { file, csv }), _) => {
+            if let Err(e) = leaderboard::leaderboard(&file, csv) {
+                eprintln!("{}: {}", file.display(), e);
+                std::process::exit(1);
+            }
+        }
+        (Some(Command::Fs { input, listing }), _) => {
+            if let Err(e) = day07::explore(input.as_deref(), &listing) {
+                eprintln!("{}", e);
+                std::process::exit(1);
+            }
+        }
+        (None, Some(day)) => {
+            let mut ctx = Context::default();
+            ctx.input_file = args.input;
+            if let Some(top) = args.top {
+                ctx.set("top", &top.to_string());
+            }
+            if let Some(rules) = &args.rules {
+                ctx.set("rules", rules);
+            }
+            if args.analyse {
+                ctx.set("analyse", "true");
+            }
+            if args.coverage {
+                ctx.set("coverage", "true");
+            }
+            if let Some(capacity) = args.capacity {
+                ctx.set("capacity", &capacity.to_string());
+            }
+            if let Some(item) = args.find_crate {
+                ctx.set("find_crate", &item.to_string());
+            }
+            if let Some(step) = &args.show_move {
+                ctx.set("show_move", step);
+            }
+            if let Some(disk_size) = args.disk_size {
+                ctx.set("disk_size", &disk_size.to_string());
+            }
+            if let Some(required_space) = args.required_space {
+                ctx.set("required_space", &required_space.to_string());
+            }
+            if let Some(plan) = &args.plan {
+                ctx.set("plan", plan);
+            }
+            #[cfg(not(feature = "alloc-stats"))]
+            run_day(day, &ctx);
+            // Each part runs on its own so its figures leave out the other's work.
+            #[cfg(feature = "alloc-stats")]
+            if SEPARATE_PARTS.contains(&day) {
+                for part in [Part::A, Part::B] {
+                    ctx.part = Some(part);
+                    let (_, stats) = alloc_stats::measure(|| run_day(day, &ctx));
+                    println!("Day {} part {}: {}", day, part, stats);
+                }
+            } else {
+                println!(
+                    "Day {}: {}",
+                    day,
+                    alloc_stats::measure(|| run_day(day, &ctx)).1
+                );
+            }
+        }
+        (None, None) => Cli::clap().print_help().unwrap(),
     }
 }
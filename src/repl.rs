@@ -0,0 +1,144 @@
+use std::{
+    io::{self, BufRead, Write},
+    panic::{self, AssertUnwindSafe},
+    time::Instant,
+};
+
+use crate::{
+    context::{Context, Part},
+    run_day,
+    validate::validate,
+};
+
+const HELP: &str = "\
+Commands:
+  day <n>            select a day (drops any pasted input)
+  paste              read input until a blank line
+  reset              go back to the day's bundled input
+  part a | part b    solve a single part
+  run                solve both parts
+  time               solve both parts and report how long it took
+  set <key> <value>  override a constant, e.g. `set target_y 10`
+  show               print the current day, input and settings
+  help | quit";
+
+struct Session {
+    day: Option<u8>,
+    ctx: Context,
+}
+
+impl Session {
+    // Runs the selected day, showing a panic (usually a parse error) as an
+    // inline error rather than ending the session.
+    fn solve(&mut self, part: Option<Part>, timed: bool) {
+        let day = match self.day {
+            Some(day) => day,
+            None => return println!("Pick a day first, e.g. `day 13`."),
+        };
+        self.ctx.part = part;
+
+        // Catch snippets the solver would choke on before it gets to them.
+        if let Some(diagnostics) = self.ctx.input.as_deref().and_then(|i| validate(day, i)) {
+            if !diagnostics.is_empty() {
+                for d in diagnostics {
+                    println!("Error: {}", d);
+                }
+                return;
+            }
+        }
+
+        let start = Instant::now();
+        let result = panic::catch_unwind(AssertUnwindSafe(|| run_day(day, &self.ctx)));
+        if let Err(e) = result {
+            let message = e
+                .downcast_ref::<String>()
+                .map(|s| s.as_str())
+                .or_else(|| e.downcast_ref::<&str>().copied())
+                .unwrap_or("unknown error");
+            println!("Error: {}", message);
+        }
+        self.ctx.flush();
+        if timed {
+            println!("Took {:?}", start.elapsed());
+        }
+    }
+
+    fn show(&self) {
+        match self.day {
+            Some(day) => println!("Day {}", day),
+            None => println!("No day selected"),
+        }
+        match &self.ctx.input {
+            Some(input) => println!("Pasted input ({} lines)", input.lines().count()),
+            None => println!("Bundled input"),
+        }
+        for (key, value) in self.ctx.settings() {
+            println!("{} = {}", key, value);
+        }
+    }
+}
+
+fn read_paste(lines: &mut impl Iterator<Item = io::Result<String>>) -> String {
+    lines
+        .map_while(Result::ok)
+        .take_while(|l| !l.trim().is_empty())
+        .collect::<Vec<_>>()
+        .join("\n")
+}
+
+pub fn repl() {
+    println!("{}", HELP);
+    let mut session = Session {
+        day: None,
+        ctx: Context::default(),
+    };
+
+    // Panics are reported inline by `solve` - don't also dump them to stderr.
+    panic::set_hook(Box::new(|_| {}));
+
+    let stdin = io::stdin();
+    let mut lines = stdin.lock().lines();
+    loop {
+        print!("> ");
+        io::stdout().flush().unwrap();
+        let line = match lines.next() {
+            Some(Ok(line)) => line,
+            _ => break,
+        };
+
+        let words = line.split_whitespace().collect::<Vec<_>>();
+        match words.as_slice() {
+            [] => {}
+            ["day", n] => match n.parse() {
+                Ok(day) if (1..=25).contains(&day) => {
+                    session.day = Some(day);
+                    session.ctx.input = None;
+                }
+                _ => println!("Not a day: {}", n),
+            },
+            ["paste"] => {
+                let input = read_paste(&mut lines);
+                println!("Read {} lines.", input.lines().count());
+                session.ctx.input = Some(input);
+            }
+            ["reset"] => session.ctx.input = None,
+            ["part", "a"] | ["part", "A"] => session.solve(Some(Part::A), false),
+            ["part", "b"] | ["part", "B"] => session.solve(Some(Part::B), false),
+            ["run"] => session.solve(None, false),
+            ["time"] => session.solve(None, true),
+            ["set", key, value] => session.ctx.set(key, value),
+            ["show"] => session.show(),
+            ["help"] => println!("{}", HELP),
+            ["quit"] | ["exit"] => break,
+            _ => println!("Unknown command: {} (try `help`)", line.trim()),
+        }
+    }
+    let _ = panic::take_hook();
+}
+
+#[test]
+fn paste_stops_at_blank_line() {
+    let mut lines = "1000\n2000\n  \n3000\n".lines().map(|l| Ok(l.to_string()));
+    assert_eq!("1000\n2000", read_paste(&mut lines));
+    assert_eq!(Some("3000".to_string()), lines.next().map(|l| l.unwrap()));
+}
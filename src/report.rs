@@ -0,0 +1,273 @@
+use std::{collections::BTreeMap, env, fs, io, path::Path, time::Duration};
+
+use crate::runner::{self, Run};
+
+struct DayReport {
+    day: u8,
+    runs: Vec<Run>,
+    answers: BTreeMap<String, String>,
+    expected: BTreeMap<String, String>,
+}
+
+impl DayReport {
+    fn status(&self) -> &'static str {
+        if self.runs.iter().any(|r| !r.success) {
+            "failed"
+        } else if self.expected.is_empty() {
+            "unverified"
+        } else if self
+            .expected
+            .iter()
+            .all(|(p, a)| self.answers.get(p) == Some(a))
+        {
+            "verified"
+        } else {
+            "mismatch"
+        }
+    }
+
+    fn timings(&self) -> Vec<f64> {
+        self.runs.iter().map(|r| millis(r.elapsed)).collect()
+    }
+
+    fn mean(&self) -> f64 {
+        let t = self.timings();
+        t.iter().sum::<f64>() / t.len() as f64
+    }
+
+    // What the day wrote to its diagnostics channel, cut short for chatty days.
+    fn diagnostics(&self) -> String {
+        let stderr = &self.runs.last().unwrap().stderr;
+        let lines = stderr.lines().count();
+        if lines <= MAX_DIAGNOSTIC_LINES {
+            return stderr.to_string();
+        }
+        let mut shown = stderr
+            .lines()
+            .take(MAX_DIAGNOSTIC_LINES)
+            .collect::<Vec<_>>()
+            .join("\n");
+        shown.push_str(&format!(
+            "\n... {} more lines",
+            lines - MAX_DIAGNOSTIC_LINES
+        ));
+        shown
+    }
+
+    // Only present when the binary was built with `--features alloc-stats`.
+    fn memory(&self) -> BTreeMap<String, String> {
+        runner::parse_memory(&self.runs.last().unwrap().stdout)
+    }
+}
+
+const MAX_DIAGNOSTIC_LINES: usize = 200;
+
+fn millis(d: Duration) -> f64 {
+    d.as_secs_f64() * 1000.0
+}
+
+fn escape(s: &str) -> String {
+    s.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+}
+
+// Multi-line answers (day 10's screen) only read properly preformatted.
+fn answer_cell(answer: Option<&String>) -> String {
+    match answer {
+        Some(a) if a.contains('\n') => format!("<pre>{}</pre>", escape(a)),
+        Some(a) => escape(a),
+        None => "-".to_string(),
+    }
+}
+
+// Expected answers, one "<day> <part> <answer>" per line, e.g. "1 A 72017".
+fn load_expected(path: &Path) -> BTreeMap<(u8, String), String> {
+    let mut expected = BTreeMap::new();
+    if let Ok(contents) = fs::read_to_string(path) {
+        for line in contents.lines() {
+            let mut parts = line.splitn(3, ' ');
+            if let (Some(day), Some(part), Some(answer)) =
+                (parts.next(), parts.next(), parts.next())
+            {
+                if let Ok(day) = day.parse() {
+                    expected.insert((day, part.to_string()), answer.to_string());
+                }
+            }
+        }
+    }
+    expected
+}
+
+// Horizontal bars, one per labelled value, scaled to the largest.
+fn bar_chart(bars: &[(String, f64)]) -> String {
+    let max = bars
+        .iter()
+        .map(|(_, v)| *v)
+        .fold(0.0, f64::max)
+        .max(f64::EPSILON);
+    let mut svg = format!(
+        r#"<svg xmlns="http://www.w3.org/2000/svg" width="560" height="{}" font-size="12">"#,
+        bars.len() * 20 + 4
+    );
+    for (i, (label, value)) in bars.iter().enumerate() {
+        let y = i * 20 + 2;
+        let width = value / max * 360.0;
+        svg.push_str(&format!(
+            r##"<text x="0" y="{}">{}</text><rect x="80" y="{}" width="{:.1}" height="16" fill="#4a7ebb"/><text x="{:.1}" y="{}">{:.2} ms</text>"##,
+            y + 13,
+            escape(label),
+            y,
+            width,
+            width + 84.0,
+            y + 13,
+            value
+        ));
+    }
+    svg.push_str("</svg>");
+    svg
+}
+
+fn day_section(r: &DayReport) -> String {
+    let mut html = format!(r#"<section id="day{:02}"><h2>Day {}</h2>"#, r.day, r.day);
+    html.push_str(&format!(r#"<p class="{}">{}</p>"#, r.status(), r.status()));
+
+    html.push_str("<table><tr><th>Part</th><th>Answer</th><th>Expected</th></tr>");
+    for part in ["A", "B"] {
+        html.push_str(&format!(
+            "<tr><td>{}</td><td>{}</td><td>{}</td></tr>",
+            part,
+            answer_cell(r.answers.get(part)),
+            answer_cell(r.expected.get(part))
+        ));
+    }
+    html.push_str("</table>");
+
+    let timings = r.timings();
+    let min = timings.iter().cloned().fold(f64::INFINITY, f64::min);
+    let max = timings.iter().cloned().fold(0.0, f64::max);
+    html.push_str(&format!(
+        "<h3>Timings</h3><p>{} runs: min {:.2} ms, mean {:.2} ms, max {:.2} ms</p>",
+        timings.len(),
+        min,
+        r.mean(),
+        max
+    ));
+    let bars = timings
+        .iter()
+        .enumerate()
+        .map(|(i, t)| (format!("run {}", i + 1), *t))
+        .collect::<Vec<_>>();
+    html.push_str(&bar_chart(&bars));
+
+    let memory = r.memory();
+    if !memory.is_empty() {
+        html.push_str("<h3>Memory</h3><table><tr><th>Part</th><th>Allocations</th></tr>");
+        for (part, stats) in &memory {
+            html.push_str(&format!(
+                "<tr><td>{}</td><td>{}</td></tr>",
+                part,
+                escape(stats)
+            ));
+        }
+        html.push_str("</table>");
+    }
+
+    let diagnostics = r.diagnostics();
+    if !diagnostics.trim().is_empty() {
+        html.push_str(&format!(
+            "<details><summary>Diagnostics</summary><pre>{}</pre></details>",
+            escape(&diagnostics)
+        ));
+    }
+    html.push_str("</section>");
+    html
+}
+
+fn render(reports: &[DayReport]) -> String {
+    let mut html = String::from(
+        r#"<!DOCTYPE html>
+<html><head><meta charset="utf-8"><title>Advent of Code 2022</title>
+<style>
+body { font-family: sans-serif; max-width: 900px; margin: auto; }
+table { border-collapse: collapse; } td, th { border: 1px solid #ccc; padding: 2px 8px; }
+pre { background: #f4f4f4; padding: 8px; overflow-x: auto; }
+.verified { color: green; } .mismatch, .failed { color: red; } .unverified { color: grey; }
+</style></head><body><h1>Advent of Code 2022</h1>"#,
+    );
+
+    html.push_str(
+        "<table><tr><th>Day</th><th>Part A</th><th>Part B</th><th>Status</th><th>Mean</th></tr>",
+    );
+    for r in reports {
+        html.push_str(&format!(
+            r##"<tr><td><a href="#day{:02}">{}</a></td><td>{}</td><td>{}</td><td class="{}">{}</td><td>{:.2} ms</td></tr>"##,
+            r.day,
+            r.day,
+            answer_cell(r.answers.get("A")),
+            answer_cell(r.answers.get("B")),
+            r.status(),
+            r.status(),
+            r.mean()
+        ));
+    }
+    html.push_str("</table>");
+    let means = reports
+        .iter()
+        .map(|r| (format!("day {}", r.day), r.mean()))
+        .collect::<Vec<_>>();
+    html.push_str(&bar_chart(&means));
+
+    for r in reports {
+        html.push_str(&day_section(r));
+    }
+    html.push_str("</body></html>\n");
+    html
+}
+
+pub fn report(out: &Path, days: &[u8], runs: usize, answers: &Path) -> io::Result<()> {
+    let exe = env::current_exe()?;
+    let expected = load_expected(answers);
+
+    let mut reports = vec![];
+    for &day in days {
+        println!("Running day {}...", day);
+        let runs = (0..runs.max(1))
+            .map(|_| runner::run(&exe, day))
+            .collect::<Vec<_>>();
+        let answers = runner::parse_answers(&runs.last().unwrap().stdout);
+        let expected = expected
+            .iter()
+            .filter(|((d, _), _)| *d == day)
+            .map(|((_, p), a)| (p.clone(), a.clone()))
+            .collect();
+        reports.push(DayReport {
+            day,
+            runs,
+            answers,
+            expected,
+        });
+    }
+
+    fs::create_dir_all(out)?;
+    let path = out.join("index.html");
+    fs::write(&path, render(&reports))?;
+    println!("Report written to {}", path.display());
+    Ok(())
+}
+
+#[test]
+fn expected_answers_file() {
+    let path = env::temp_dir().join("aoc22-expected-answers.txt");
+    fs::write(&path, "1 A 72017\n1 B 212520\nnonsense\n10 B #..#\n").unwrap();
+    let expected = load_expected(&path);
+    assert_eq!(
+        Some(&"72017".to_string()),
+        expected.get(&(1, "A".to_string()))
+    );
+    assert_eq!(
+        Some(&"#..#".to_string()),
+        expected.get(&(10, "B".to_string()))
+    );
+    assert_eq!(3, expected.len());
+}
@@ -0,0 +1,100 @@
+use std::{
+    collections::BTreeMap,
+    path::Path,
+    process::Command,
+    time::{Duration, Instant},
+};
+
+use regex::Regex;
+
+// The outcome of running a single day in a child process.
+pub struct Run {
+    pub stdout: String,
+    pub stderr: String,
+    pub success: bool,
+    pub elapsed: Duration,
+}
+
+// Runs `exe <day>`, capturing its output. Timings are wall-clock, so include
+// process start-up.
+pub fn run(exe: &Path, day: u8) -> Run {
+    let start = Instant::now();
+    let output = Command::new(exe).arg(day.to_string()).output().unwrap();
+    let elapsed = start.elapsed();
+    Run {
+        stdout: String::from_utf8_lossy(&output.stdout).to_string(),
+        stderr: String::from_utf8_lossy(&output.stderr).to_string(),
+        success: output.status.success(),
+        elapsed,
+    }
+}
+
+// Pulls "Part A is: 123" style lines out of a day's output, keyed by part. An
+// answer spanning several lines starts on the line after "Part B is:".
+pub fn parse_answers(output: &str) -> BTreeMap<String, String> {
+    let re = Regex::new(r"^Part ([AB])(?: answer)? is:?(?: (.*))?$").unwrap();
+    let other = Regex::new(r"^Day \d+(?: part [AB])?: ").unwrap();
+    let mut answers = BTreeMap::new();
+    let mut block: Option<(String, Vec<&str>)> = None;
+    for line in output.lines() {
+        let cap = re.captures(line);
+        if cap.is_some() || other.is_match(line) {
+            if let Some((part, lines)) = block.take() {
+                answers.insert(part, lines.join("\n"));
+            }
+        }
+        match cap {
+            Some(cap) => match cap.get(2) {
+                Some(answer) => {
+                    answers.insert(cap[1].to_string(), answer.as_str().to_string());
+                }
+                None => block = Some((cap[1].to_string(), vec![])),
+            },
+            None => {
+                if let Some((_, lines)) = &mut block {
+                    lines.push(line);
+                }
+            }
+        }
+    }
+    if let Some((part, lines)) = block {
+        answers.insert(part, lines.join("\n"));
+    }
+    answers
+}
+
+// Allocation figures keyed by part, or by "A and B" for days measured as a
+// whole. Only printed by a binary built with `--features alloc-stats`.
+pub fn parse_memory(output: &str) -> BTreeMap<String, String> {
+    let re = Regex::new(r"^Day \d+(?: part ([AB]))?: (.* allocations.*)$").unwrap();
+    output
+        .lines()
+        .filter_map(|l| re.captures(l))
+        .map(|cap| {
+            let part = cap.get(1).map_or("A and B", |p| p.as_str());
+            (part.to_string(), cap[2].to_string())
+        })
+        .collect()
+}
+
+#[test]
+fn answers_from_output() {
+    let answers = parse_answers("Part A answer is: 24000\nnoise\nPart B is 45000");
+    assert_eq!(Some(&"24000".to_string()), answers.get("A"));
+    assert_eq!(Some(&"45000".to_string()), answers.get("B"));
+
+    let answers =
+        parse_answers("Part A is: 13140\nPart B is:\n##..\n###.\nDay 10 part B: 3 allocations");
+    assert_eq!(Some(&"##..\n###.".to_string()), answers.get("B"));
+}
+
+#[test]
+fn memory_from_output() {
+    let memory =
+        parse_memory("Part A is: 1\nDay 1 part A: 3 allocations\nDay 1 part B: 0 allocations");
+    assert_eq!(Some(&"3 allocations".to_string()), memory.get("A"));
+    assert_eq!(Some(&"0 allocations".to_string()), memory.get("B"));
+
+    let memory = parse_memory("Part A is: 1\nPart B is: 2\nDay 4: 3 allocations");
+    assert_eq!(Some(&"3 allocations".to_string()), memory.get("A and B"));
+}
@@ -0,0 +1,110 @@
+use std::{fmt, fs, io, path::Path};
+
+use crate::{day05, day07, day08, day17, day22, day24};
+
+// A broken assumption about the shape of a puzzle input.
+#[derive(Debug, PartialEq, Eq)]
+pub struct Diagnostic {
+    // 1-based, when the problem can be pinned to a line.
+    pub line: Option<usize>,
+    pub message: String,
+}
+
+impl Diagnostic {
+    pub fn new(message: impl Into<String>) -> Diagnostic {
+        Diagnostic {
+            line: None,
+            message: message.into(),
+        }
+    }
+
+    // Takes the 0-based index that `lines().enumerate()` hands out.
+    pub fn at(index: usize, message: impl Into<String>) -> Diagnostic {
+        Diagnostic {
+            line: Some(index + 1),
+            message: message.into(),
+        }
+    }
+}
+
+impl fmt::Display for Diagnostic {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self.line {
+            Some(line) => write!(f, "line {}: {}", line, self.message),
+            None => write!(f, "{}", self.message),
+        }
+    }
+}
+
+// None for days whose solvers don't make any assumptions worth checking.
+pub fn validate(day: u8, input: &str) -> Option<Vec<Diagnostic>> {
+    match day {
+        5 => Some(day05::validate(input)),
+        7 => Some(day07::validate(input)),
+        8 => Some(day08::validate(input)),
+        17 => Some(day17::validate(input)),
+        22 => Some(day22::validate(input)),
+        24 => Some(day24::validate(input)),
+        _ => None,
+    }
+}
+
+fn bundled_input(day: u8) -> Option<&'static str> {
+    match day {
+        5 => Some(include_str!("../inputs/day05.txt")),
+        7 => Some(include_str!("../inputs/day07.txt")),
+        8 => Some(include_str!("../inputs/day08.txt")),
+        17 => Some(include_str!("../inputs/day17.txt")),
+        22 => Some(include_str!("../inputs/day22.txt")),
+        24 => Some(include_str!("../inputs/day24.txt")),
+        _ => None,
+    }
+}
+
+// Prints any diagnostics, returning whether the input looks solvable.
+pub fn check(day: u8, input: Option<&Path>) -> io::Result<bool> {
+    let input = match input {
+        Some(path) => fs::read_to_string(path)?,
+        None => match bundled_input(day) {
+            Some(input) => input.to_string(),
+            None => {
+                println!("No checks for day {}", day);
+                return Ok(true);
+            }
+        },
+    };
+
+    match validate(day, &input) {
+        None => println!("No checks for day {}", day),
+        Some(diagnostics) if diagnostics.is_empty() => println!("Day {} input looks good", day),
+        Some(diagnostics) => {
+            for d in &diagnostics {
+                println!("{}", d);
+            }
+            println!("Day {} input has {} problem(s)", day, diagnostics.len());
+            return Ok(false);
+        }
+    }
+    Ok(true)
+}
+
+#[test]
+fn bundled_inputs_pass() {
+    for day in [5, 7, 8, 17, 22, 24] {
+        let diagnostics = validate(day, bundled_input(day).unwrap()).unwrap();
+        assert!(diagnostics.is_empty(), "day {}: {:?}", day, diagnostics);
+    }
+}
+
+#[test]
+fn other_jets_are_rejected() {
+    let jets = crate::generate::generate(17, 1, 10).unwrap();
+    let diagnostics = validate(17, jets.trim_end()).unwrap();
+    assert_eq!(
+        vec!["the cycle was observed with 10091 jets, not 10".to_string()],
+        diagnostics
+            .iter()
+            .map(|d| d.to_string())
+            .collect::<Vec<_>>()
+    );
+}
@@ -0,0 +1,121 @@
+use std::{
+    collections::BTreeMap,
+    env, fs,
+    path::{Path, PathBuf},
+    process::Command,
+    thread,
+    time::{Duration, SystemTime},
+};
+
+use crate::runner;
+
+const POLL_INTERVAL: Duration = Duration::from_millis(500);
+
+// Everything that should trigger a re-run of the given day. Days lean on
+// shared modules, so any source file counts.
+fn watched_paths(day: u8) -> Vec<PathBuf> {
+    let mut paths = vec![PathBuf::from(format!("inputs/day{:02}.txt", day))];
+    if let Ok(entries) = fs::read_dir("src") {
+        paths.extend(entries.flatten().map(|entry| entry.path()));
+    }
+
+    // The examples directory is optional - only pick up files for this day.
+    let prefix = format!("day{:02}", day);
+    if let Ok(entries) = fs::read_dir("examples") {
+        for entry in entries.flatten() {
+            if entry.file_name().to_string_lossy().starts_with(&prefix) {
+                paths.push(entry.path());
+            }
+        }
+    }
+    paths
+}
+
+fn snapshot(day: u8) -> BTreeMap<PathBuf, Option<SystemTime>> {
+    watched_paths(day)
+        .into_iter()
+        .map(|p| {
+            let modified = fs::metadata(&p).and_then(|m| m.modified()).ok();
+            (p, modified)
+        })
+        .collect()
+}
+
+// The build that made this binary, so rebuilding replaces it rather than some
+// other profile's.
+fn build_args() -> Vec<&'static str> {
+    let mut args = vec!["build", "--quiet"];
+    if !cfg!(debug_assertions) {
+        args.push("--release");
+    }
+    if cfg!(feature = "alloc-stats") {
+        args.extend(["--features", "alloc-stats"]);
+    }
+    args
+}
+
+// Rebuilds and runs the day, returning its answers if it ran successfully.
+fn run(exe: &Path, day: u8) -> Option<BTreeMap<String, String>> {
+    let build = Command::new("cargo").args(build_args()).status();
+    if !matches!(build, Ok(s) if s.success()) {
+        println!("Build failed - waiting for changes.");
+        return None;
+    }
+
+    let run = runner::run(exe, day);
+    print!("{}", run.stdout);
+    print!("{}", run.stderr);
+    if !run.success {
+        println!("Day {} failed after {:?}", day, run.elapsed);
+        return None;
+    }
+    println!("Day {} finished in {:?}", day, run.elapsed);
+    Some(runner::parse_answers(&run.stdout))
+}
+
+fn print_diff(previous: &BTreeMap<String, String>, current: &BTreeMap<String, String>) {
+    for (part, answer) in current {
+        match previous.get(part) {
+            Some(old) if old != answer => {
+                println!("Part {} changed:", part);
+                println!("  - {}", old);
+                println!("  + {}", answer);
+            }
+            _ => {}
+        }
+    }
+    for (part, old) in previous {
+        if !current.contains_key(part) {
+            println!("Part {} no longer reported:", part);
+            println!("  - {}", old);
+        }
+    }
+}
+
+pub fn watch(day: u8) {
+    println!("Watching day {} - Ctrl-C to stop.", day);
+    let mut seen = snapshot(day);
+    // Resolve this before the first rebuild - once cargo replaces the binary,
+    // current_exe() points at the deleted original.
+    let exe = env::current_exe().unwrap();
+    let mut answers = run(&exe, day);
+
+    loop {
+        thread::sleep(POLL_INTERVAL);
+        let current = snapshot(day);
+        if current == seen {
+            continue;
+        }
+        seen = current;
+
+        println!("\n--- Change detected, re-running day {} ---", day);
+        let fresh = run(&exe, day);
+        if let (Some(previous), Some(current)) = (&answers, &fresh) {
+            print_diff(previous, current);
+        }
+        // Keep the last good answers around so a failed run doesn't reset the diff.
+        if fresh.is_some() {
+            answers = fresh;
+        }
+    }
+}